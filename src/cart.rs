@@ -0,0 +1,51 @@
+use std::io::Write;
+
+use crate::data::AddToCartDatum;
+use crate::Section;
+
+/// Cart entries of every class in the schedule
+fn entries<'a>(schedule: &[&'a Section]) -> Vec<&'a AddToCartDatum> {
+    schedule
+        .iter()
+        .filter(|x| !x.busy)
+        .flat_map(|x| x.cart.iter())
+        .collect()
+}
+
+pub fn write_cart_json(writer: impl Write, schedule: &[&Section]) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(writer, &entries(schedule))
+}
+
+/// Quote a CSV field if needed
+fn field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+pub fn write_cart_csv(mut writer: impl Write, schedule: &[&Section]) -> std::io::Result<()> {
+    writeln!(
+        writer,
+        "class_nbr,crse_id,catalog_nbr,session_code,course_career,unt_taken,grading_basis,rqmnt_designtn,wait_list_okay"
+    )?;
+
+    for entry in entries(schedule) {
+        let fields = [
+            &entry.class_nbr,
+            &entry.crse_id,
+            &entry.catalog_nbr,
+            &entry.session_code,
+            &entry.course_career,
+            &entry.unt_taken,
+            &entry.grading_basis,
+            &entry.rqmnt_designtn,
+            &entry.wait_list_okay,
+        ];
+
+        writeln!(writer, "{}", fields.map(|x| field(x)).join(","))?;
+    }
+
+    Ok(())
+}
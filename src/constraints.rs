@@ -0,0 +1,302 @@
+use std::fmt::Display;
+
+use crate::requisite::missing_corequisites;
+use crate::{Day, Section, Time, TimeRange};
+
+/// Every day with at least one class
+pub fn class_days(schedule: &[&Section]) -> Vec<Day> {
+    let mut days = Vec::new();
+
+    for meeting in schedule
+        .iter()
+        .filter(|x| !x.busy)
+        .flat_map(|x| x.meetings.iter())
+    {
+        if !days.contains(&meeting.meet_day) {
+            days.push(meeting.meet_day);
+        }
+    }
+
+    days
+}
+
+/// Times of everything in the schedule on `day`, sorted by start time
+pub fn day_times(schedule: &[&Section], day: Day) -> Vec<TimeRange> {
+    let mut times: Vec<TimeRange> = schedule
+        .iter()
+        .flat_map(|x| x.meetings.iter())
+        .filter(|x| x.meet_day == day)
+        .map(|x| x.time)
+        .collect();
+
+    times.sort_by_key(|x| x.start);
+    times
+}
+
+/// Times on each day that `added` meets, with `added` included, so a partial
+/// schedule only has to be checked again on the days that changed. Busy
+/// sections are left out unless `busy` is set.
+fn changed_days(schedule: &[&Section], added: &Section, busy: bool) -> Vec<(Day, Vec<TimeRange>)> {
+    if added.busy && !busy {
+        return Vec::new();
+    }
+
+    let mut days = Vec::new();
+    for meeting in &added.meetings {
+        if !days.contains(&meeting.meet_day) {
+            days.push(meeting.meet_day);
+        }
+    }
+
+    days.into_iter()
+        .map(|day| {
+            let mut times: Vec<TimeRange> = schedule
+                .iter()
+                .filter(|x| busy || !x.busy)
+                .chain([&added])
+                .flat_map(|x| x.meetings.iter())
+                .filter(|x| x.meet_day == day)
+                .map(|x| x.time)
+                .collect();
+            times.sort_by_key(|x| x.start);
+
+            (day, times)
+        })
+        .collect()
+}
+
+/// Longest free stretch inside of `window`, in minutes
+fn longest_free(times: &[TimeRange], window: TimeRange) -> u32 {
+    let mut longest = 0;
+    let mut cursor = window.start;
+
+    for time in times {
+        if time.start > cursor {
+            longest = longest.max(time.start.min(window.end).into_min() - cursor.into_min());
+        }
+        cursor = cursor.max(time.end);
+
+        if cursor >= window.end {
+            return longest;
+        }
+    }
+
+    longest.max(window.end.into_min() - cursor.into_min())
+}
+
+/// Minutes between each pair of consecutive times
+pub fn gaps(times: &[TimeRange]) -> impl Iterator<Item = u32> + '_ {
+    times
+        .windows(2)
+        .map(|x| x[1].start.into_min().saturating_sub(x[0].end.into_min()))
+}
+
+/// Length in minutes of each run of times without a break of at least `break_length`
+pub fn stretches(times: &[TimeRange], break_length: u32) -> Vec<u32> {
+    let mut res = Vec::new();
+
+    let Some(first) = times.first() else {
+        return res;
+    };
+
+    let mut start = first.start;
+    let mut end = first.end;
+    for time in &times[1..] {
+        if time.start.into_min().saturating_sub(end.into_min()) >= break_length {
+            res.push(end.into_min() - start.into_min());
+            start = time.start;
+        }
+        end = end.max(time.end);
+    }
+    res.push(end.into_min() - start.into_min());
+
+    res
+}
+
+/// A constraint on a whole schedule rather than a pair of sections
+pub trait Constraint: Display {
+    /// Whether a partial schedule can still be completed after adding `added`
+    /// to `schedule`, which already passed this check.
+    /// This must only reject schedules which can't be fixed by adding sections.
+    fn partial(&self, _schedule: &[&Section], _added: &Section) -> bool {
+        true
+    }
+
+    /// Whether a complete schedule satisfies the constraint
+    fn complete(&self, schedule: &[&Section]) -> bool;
+}
+
+/// At least `length` free minutes inside of `window` on every day with class
+pub struct MealBreak {
+    pub window: TimeRange,
+    pub length: u32,
+}
+
+impl Display for MealBreak {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{} minutes free during {}",
+            self.length, self.window
+        ))
+    }
+}
+
+impl Constraint for MealBreak {
+    fn partial(&self, schedule: &[&Section], added: &Section) -> bool {
+        let days = class_days(schedule);
+
+        changed_days(schedule, added, true)
+            .into_iter()
+            .filter(|(day, _)| !added.busy || days.contains(day))
+            .all(|(_, times)| longest_free(&times, self.window) >= self.length)
+    }
+
+    fn complete(&self, schedule: &[&Section]) -> bool {
+        class_days(schedule)
+            .into_iter()
+            .all(|day| longest_free(&day_times(schedule, day), self.window) >= self.length)
+    }
+}
+
+/// At most this many minutes of class on any day
+pub struct MaxDailyMinutes(pub u32);
+
+impl Display for MaxDailyMinutes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "At most {} of class a day",
+            Time::from_min(self.0)
+        ))
+    }
+}
+
+impl Constraint for MaxDailyMinutes {
+    fn partial(&self, schedule: &[&Section], added: &Section) -> bool {
+        changed_days(schedule, added, false)
+            .into_iter()
+            .all(|(_, times)| times.iter().map(TimeRange::len).sum::<u32>() <= self.0)
+    }
+
+    fn complete(&self, schedule: &[&Section]) -> bool {
+        let classes: Vec<&Section> = schedule.iter().filter(|x| !x.busy).copied().collect();
+
+        class_days(&classes).into_iter().all(|day| {
+            day_times(&classes, day)
+                .iter()
+                .map(TimeRange::len)
+                .sum::<u32>()
+                <= self.0
+        })
+    }
+}
+
+/// At most `minutes` of class without a break of at least `break_length` minutes
+pub struct MaxConsecutive {
+    pub minutes: u32,
+    pub break_length: u32,
+}
+
+impl Display for MaxConsecutive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "At most {} of class without a {} minute break",
+            Time::from_min(self.minutes),
+            self.break_length
+        ))
+    }
+}
+
+impl Constraint for MaxConsecutive {
+    fn partial(&self, schedule: &[&Section], added: &Section) -> bool {
+        changed_days(schedule, added, false)
+            .into_iter()
+            .all(|(_, times)| {
+                stretches(&times, self.break_length)
+                    .into_iter()
+                    .all(|x| x <= self.minutes)
+            })
+    }
+
+    fn complete(&self, schedule: &[&Section]) -> bool {
+        let classes: Vec<&Section> = schedule.iter().filter(|x| !x.busy).copied().collect();
+
+        class_days(&classes).into_iter().all(|day| {
+            stretches(&day_times(&classes, day), self.break_length)
+                .into_iter()
+                .all(|x| x <= self.minutes)
+        })
+    }
+}
+
+/// Class on at most this many days of the week
+pub struct MaxDays(pub usize);
+
+impl Display for MaxDays {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Class on at most {} days", self.0))
+    }
+}
+
+impl Constraint for MaxDays {
+    fn partial(&self, schedule: &[&Section], added: &Section) -> bool {
+        let mut days = class_days(schedule);
+        for day in class_days(&[added]) {
+            if !days.contains(&day) {
+                days.push(day);
+            }
+        }
+
+        days.len() <= self.0
+    }
+
+    fn complete(&self, schedule: &[&Section]) -> bool {
+        class_days(schedule).len() <= self.0
+    }
+}
+
+/// At most this many minutes between classes over the whole week
+pub struct MaxGaps(pub u32);
+
+impl Display for MaxGaps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "At most {} between classes a week",
+            Time::from_min(self.0)
+        ))
+    }
+}
+
+impl Constraint for MaxGaps {
+    // Adding a class can fill a gap, so partial schedules can't be pruned
+
+    fn complete(&self, schedule: &[&Section]) -> bool {
+        let classes: Vec<&Section> = schedule.iter().filter(|x| !x.busy).copied().collect();
+
+        class_days(&classes)
+            .into_iter()
+            .map(|day| gaps(&day_times(&classes, day)).sum::<u32>())
+            .sum::<u32>()
+            <= self.0
+    }
+}
+
+/// Every class is taken with its corequisites, unless they were already completed
+pub struct Corequisites {
+    /// Pairs of a class and the class it has to be taken with
+    pub rules: &'static [(&'static str, &'static str)],
+    pub completed: &'static [&'static str],
+}
+
+impl Display for Corequisites {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Corequisites taken together")
+    }
+}
+
+impl Constraint for Corequisites {
+    // The corequisite may be added later, so partial schedules can't be pruned
+
+    fn complete(&self, schedule: &[&Section]) -> bool {
+        missing_corequisites(schedule, self.rules, self.completed).is_empty()
+    }
+}
@@ -0,0 +1,168 @@
+use crate::Section;
+
+/// Course codes in a description like `Cross-listed with MA 341, MA 405`
+pub fn parse_course_codes(descr: &str) -> Vec<String> {
+    let words: Vec<&str> = descr
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    let mut codes = Vec::new();
+    for pair in words.windows(2) {
+        let (subject, number) = (pair[0], pair[1]);
+
+        let is_subject =
+            (1..=4).contains(&subject.len()) && subject.chars().all(|c| c.is_ascii_uppercase());
+        let is_number = number.len() >= 3 && number.chars().take(3).all(|c| c.is_ascii_digit());
+
+        if is_subject && is_number {
+            let code = format!("{subject} {number}");
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+    }
+
+    codes
+}
+
+/// Whether both sections are the same class meeting listed under different codes
+fn same_meeting(a: &Section, b: &Section) -> bool {
+    a.meetings == b.meetings && a.professor == b.professor && a.facility == b.facility
+}
+
+/// Whether `a` and `b` are the same course under different codes
+fn cross_listed(a: &[Section], b: &[Section]) -> bool {
+    let lists = |a: &[Section], b: &[Section]| {
+        a.iter()
+            .any(|x| b.iter().any(|y| x.cross_listed.contains(&y.class)))
+    };
+
+    lists(a, b) || lists(b, a)
+}
+
+/// Merge cross-listed courses into the first of them, so the course can only be
+/// taken once. A section that is the same meeting as one already in the pool is
+/// dropped, keeping its class number as an alias. Returns the new index of each
+/// course.
+pub fn merge_cross_listed(classes: &mut Vec<Vec<Section>>) -> Vec<usize> {
+    let mut merged: Vec<Vec<Section>> = Vec::new();
+    let mut indices = Vec::new();
+
+    for course in classes.drain(..) {
+        let Some(index) = merged.iter().position(|x| cross_listed(x, &course)) else {
+            indices.push(merged.len());
+            merged.push(course);
+            continue;
+        };
+
+        let pool = &mut merged[index];
+        let name = |x: &[Section]| x.first().map(|x| x.class.clone()).unwrap_or_default();
+        println!("{:?} is cross-listed with {:?}", name(&course), name(pool));
+
+        for section in course {
+            match pool.iter_mut().find(|x| same_meeting(x, &section)) {
+                Some(kept) => {
+                    kept.aliases.push(section.class_nbr);
+                    kept.aliases.extend(section.aliases);
+                }
+                None => pool.push(section),
+            }
+        }
+        indices.push(index);
+    }
+
+    *classes = merged;
+
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Credits, Day, DayTime, Time, TimeRange};
+
+    fn section(class: &str, class_nbr: &str, day: Day, cross_listed: &[&str]) -> Section {
+        Section {
+            class: class.to_owned(),
+            section: "001".to_owned(),
+            facility: "SAS 1102".to_owned(),
+            meetings: vec![DayTime {
+                meet_day: day,
+                time: TimeRange {
+                    start: Time::from_min(9 * 60),
+                    end: Time::from_min(10 * 60),
+                },
+                campus: None,
+                building: None,
+            }],
+            professor: "Jane Doe".to_owned(),
+            credits: Credits::default(),
+            class_nbr: class_nbr.to_owned(),
+            busy: false,
+            dates: String::new(),
+            cart: Vec::new(),
+            cross_listed: cross_listed.iter().map(|&x| x.to_owned()).collect(),
+            aliases: Vec::new(),
+            course: 0,
+            requisite_met: None,
+            requisites: String::new(),
+        }
+    }
+
+    #[test]
+    fn course_codes() {
+        assert_eq!(
+            parse_course_codes("Cross-listed with ECE 379/CSC 379"),
+            ["ECE 379", "CSC 379"]
+        );
+        assert_eq!(
+            parse_course_codes("Offered with MA 341, see the catalog for ma 405 or MA 341"),
+            ["MA 341"]
+        );
+        assert!(parse_course_codes("Taught in room 101 by appointment").is_empty());
+    }
+
+    #[test]
+    fn merges_pools() {
+        let mut classes = vec![
+            vec![
+                section("MA 305", "4001", Day::Mon, &[]),
+                section("MA 305", "4002", Day::Tue, &[]),
+            ],
+            vec![
+                section("MA 341", "5001", Day::Mon, &["MA 305"]),
+                section("MA 341", "5002", Day::Wed, &["MA 305"]),
+            ],
+        ];
+
+        assert_eq!(merge_cross_listed(&mut classes), [0, 0]);
+        assert_eq!(classes.len(), 1);
+
+        let class_nbrs: Vec<&str> = classes[0].iter().map(|x| x.class_nbr.as_str()).collect();
+        assert_eq!(class_nbrs, ["4001", "4002", "5002"]);
+        assert_eq!(classes[0][0].aliases, ["5001"]);
+        assert!(classes[0][2].aliases.is_empty());
+    }
+
+    #[test]
+    fn indices_after_merging() {
+        let mut classes = vec![
+            vec![section("CSC 246", "1001", Day::Mon, &[])],
+            vec![section("MA 305", "4001", Day::Tue, &[])],
+            vec![section("CSC 326", "2001", Day::Wed, &[])],
+            vec![section("MA 341", "5001", Day::Thu, &["MA 305"])],
+        ];
+
+        assert_eq!(merge_cross_listed(&mut classes), [0, 1, 2, 1]);
+
+        let classes: Vec<Vec<&str>> = classes
+            .iter()
+            .map(|x| x.iter().map(|x| x.class.as_str()).collect())
+            .collect();
+        assert_eq!(
+            classes,
+            [vec!["CSC 246"], vec!["MA 305", "MA 341"], vec!["CSC 326"]]
+        );
+    }
+}
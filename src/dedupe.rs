@@ -0,0 +1,94 @@
+use crate::id::schedule_id;
+use crate::{Metrics, Section};
+
+/// Sections that can replace each section of a schedule, in the same order
+pub type Alternatives<'a> = Vec<Vec<&'a Section>>;
+
+/// Whether both sections are of the same course, under the same or a cross-listed code
+fn same_course(a: &Section, b: &Section) -> bool {
+    a.class == b.class || a.cross_listed.contains(&b.class) || b.cross_listed.contains(&a.class)
+}
+
+/// Whether swapping one section for the other leaves the schedule looking the same
+fn interchangeable(a: &Section, b: &Section) -> bool {
+    same_course(a, b)
+        && a.busy == b.busy
+        && a.professor == b.professor
+        && a.meetings == b.meetings
+        && a.credits == b.credits
+}
+
+/// Pair each section of `a` with an interchangeable section of `b`
+fn matching<'a>(a: &[&Section], b: &[&'a Section]) -> Option<Vec<&'a Section>> {
+    if a.len() != b.len() {
+        return None;
+    }
+
+    let mut used = vec![false; b.len()];
+    a.iter()
+        .map(|x| {
+            let i = (0..b.len()).find(|&i| !used[i] && interchangeable(x, b[i]))?;
+            used[i] = true;
+            Some(b[i])
+        })
+        .collect()
+}
+
+/// Keep only the first of each set of equivalent schedules, returning the
+/// sections that could be swapped into each of the ones kept
+pub fn dedupe<'a>(schedules: &mut Vec<(Vec<&'a Section>, Metrics)>) -> Vec<Alternatives<'a>> {
+    let mut kept: Vec<(Vec<&'a Section>, Metrics)> = Vec::new();
+    let mut alternatives: Vec<Alternatives<'a>> = Vec::new();
+
+    for (schedule, metrics) in schedules.drain(..) {
+        let found = kept
+            .iter()
+            .enumerate()
+            .find_map(|(i, (x, _))| Some((i, matching(x, &schedule)?)));
+
+        match found {
+            Some((i, matched)) => {
+                for (j, section) in matched.into_iter().enumerate() {
+                    if kept[i].0[j] != section && !alternatives[i][j].contains(&section) {
+                        alternatives[i][j].push(section);
+                    }
+                }
+            }
+            None => {
+                alternatives.push(vec![Vec::new(); schedule.len()]);
+                kept.push((schedule, metrics));
+            }
+        }
+    }
+
+    *schedules = kept;
+
+    alternatives
+}
+
+/// List the sections that could be swapped into each schedule
+pub fn print_alternatives(schedules: &[(Vec<&Section>, Metrics)], alternatives: &[Alternatives]) {
+    for ((schedule, _), alternatives) in schedules.iter().zip(alternatives) {
+        if alternatives.iter().all(|x| x.is_empty()) {
+            continue;
+        }
+
+        println!("{} has equivalent sections", schedule_id(schedule));
+        for (section, alternatives) in schedule.iter().zip(alternatives) {
+            if alternatives.is_empty() {
+                continue;
+            }
+
+            let names: Vec<String> = alternatives
+                .iter()
+                .map(|x| format!("{} {}", x.class, x.section))
+                .collect();
+            println!(
+                "    {} {} or {}",
+                section.class,
+                section.section,
+                names.join(", ")
+            );
+        }
+    }
+}
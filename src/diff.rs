@@ -0,0 +1,91 @@
+use std::fmt::Display;
+
+use crate::Section;
+
+/// How a course differs between two schedules
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change<'a> {
+    Added(&'a Section),
+    Removed(&'a Section),
+    Swapped { from: &'a Section, to: &'a Section },
+}
+
+impl<'a> Change<'a> {
+    /// Sections to highlight when drawing either schedule
+    pub fn sections(&self) -> Vec<&'a Section> {
+        match *self {
+            Change::Added(x) | Change::Removed(x) => vec![x],
+            Change::Swapped { from, to } => vec![from, to],
+        }
+    }
+}
+
+fn times(section: &Section) -> String {
+    section
+        .meetings
+        .iter()
+        .map(|x| format!("{:?} {}", x.meet_day, x.time))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Display for Change<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added(x) => f.write_fmt(format_args!(
+                "+ {} {} ({}) {}",
+                x.class,
+                x.section,
+                x.professor,
+                times(x)
+            )),
+            Change::Removed(x) => f.write_fmt(format_args!(
+                "- {} {} ({}) {}",
+                x.class,
+                x.section,
+                x.professor,
+                times(x)
+            )),
+            Change::Swapped { from, to } => {
+                f.write_fmt(format_args!(
+                    "~ {} section {} -> {}",
+                    from.class, from.section, to.section
+                ))?;
+                if from.professor != to.professor {
+                    f.write_fmt(format_args!(
+                        "\n    professor {} -> {}",
+                        from.professor, to.professor
+                    ))?;
+                }
+                if from.meetings != to.meetings {
+                    f.write_fmt(format_args!("\n    times {} -> {}", times(from), times(to)))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Courses that were added, dropped or moved to another section going from `a` to `b`
+pub fn diff<'a>(a: &[&'a Section], b: &[&'a Section]) -> Vec<Change<'a>> {
+    let a: Vec<&Section> = a.iter().filter(|x| !x.busy).copied().collect();
+    let b: Vec<&Section> = b.iter().filter(|x| !x.busy).copied().collect();
+
+    let mut changes = Vec::new();
+
+    for &from in &a {
+        match b.iter().find(|x| x.class == from.class) {
+            Some(&to) if to != from => changes.push(Change::Swapped { from, to }),
+            Some(_) => {}
+            None => changes.push(Change::Removed(from)),
+        }
+    }
+
+    for &to in &b {
+        if !a.iter().any(|x| x.class == to.class) {
+            changes.push(Change::Added(to));
+        }
+    }
+
+    changes
+}
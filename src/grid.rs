@@ -0,0 +1,126 @@
+use crate::{Day, Section, Time};
+
+/// Minutes covered by each row of the grid
+const SLOT: u32 = 30;
+/// Width of the time labels on the left of the grid
+const LABEL_WIDTH: usize = 6;
+
+/// Characters used to draw the grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Charset {
+    Ascii,
+    Unicode,
+}
+
+impl Charset {
+    fn top(self) -> char {
+        match self {
+            Charset::Ascii => '+',
+            Charset::Unicode => '┌',
+        }
+    }
+
+    fn side(self) -> char {
+        match self {
+            Charset::Ascii => '|',
+            Charset::Unicode => '│',
+        }
+    }
+
+    fn bottom(self) -> char {
+        match self {
+            Charset::Ascii => '+',
+            Charset::Unicode => '└',
+        }
+    }
+
+    fn line(self) -> char {
+        match self {
+            Charset::Ascii => '-',
+            Charset::Unicode => '─',
+        }
+    }
+}
+
+/// Width of the terminal, or 80 when it can't be found
+pub fn terminal_width() -> usize {
+    ratatui::crossterm::terminal::size()
+        .map(|(width, _)| width as usize)
+        .ok()
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(80)
+}
+
+/// Fit `text` into exactly `width` characters
+fn fit(text: &str, width: usize) -> String {
+    let text: String = text.chars().take(width).collect();
+    format!("{text:<width$}")
+}
+
+/// Text week view of a schedule, with each line at most `width` characters wide
+pub fn week_grid(schedule: &[&Section], width: usize, charset: Charset) -> Vec<String> {
+    let meetings: Vec<(&Section, _)> = schedule
+        .iter()
+        .flat_map(|&x| x.meetings.iter().map(move |y| (x, y)))
+        .collect();
+
+    let start = meetings
+        .iter()
+        .map(|(_, x)| x.time.start.hour)
+        .min()
+        .unwrap_or(8);
+    let end = meetings
+        .iter()
+        .map(|(_, x)| x.time.end.hour + (x.time.end.minute > 0) as u32)
+        .max()
+        .unwrap_or(8 + 11);
+
+    let column = (width.saturating_sub(LABEL_WIDTH) / Day::ALL.len()).max(2);
+
+    let mut lines = Vec::new();
+
+    let mut header = " ".repeat(LABEL_WIDTH);
+    for day in Day::ALL {
+        header += &fit(&format!(" {day:?}"), column);
+    }
+    lines.push(header.trim_end().to_owned());
+    lines.push(
+        charset
+            .line()
+            .to_string()
+            .repeat(LABEL_WIDTH + column * Day::ALL.len()),
+    );
+
+    for slot in (start * 60..end * 60).step_by(SLOT as usize) {
+        let mut line = fit(&Time::from_min(slot).to_string(), LABEL_WIDTH);
+
+        for day in Day::ALL {
+            let meeting = meetings.iter().find(|(_, x)| {
+                x.meet_day == day
+                    && x.time.start.into_min() < slot + SLOT
+                    && x.time.end.into_min() > slot
+            });
+
+            let cell = match meeting {
+                // First row of a meeting
+                Some((section, x)) if x.time.start.into_min() >= slot => {
+                    format!("{}{} {}", charset.top(), section.class, section.section)
+                }
+                // Second row of a meeting
+                Some((_, x)) if x.time.start.into_min() + SLOT >= slot => {
+                    format!("{}{}", charset.side(), x.time)
+                }
+                Some((_, x)) if x.time.end.into_min() <= slot + SLOT => {
+                    charset.bottom().to_string()
+                }
+                Some(_) => charset.side().to_string(),
+                None => String::new(),
+            };
+            line += &fit(&cell, column);
+        }
+
+        lines.push(line.trim_end().to_owned());
+    }
+
+    lines
+}
@@ -0,0 +1,298 @@
+use std::io::Write;
+use std::time::SystemTime;
+
+use crate::{Day, Section, Time, TimeRange};
+
+/// A calendar date
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Date {
+    year: i64,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Parse `MM/DD/YYYY`, `MM/DD/YY` or `YYYY-MM-DD`
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        if let Some((year, rest)) = s.split_once('-') {
+            let (month, day) = rest.split_once('-')?;
+            return Some(Self {
+                year: year.parse().ok()?,
+                month: month.parse().ok()?,
+                day: day.parse().ok()?,
+            });
+        }
+
+        let mut parts = s.split('/');
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        let year: i64 = parts.next()?.parse().ok()?;
+
+        Some(Self {
+            year: if year < 100 { year + 2000 } else { year },
+            month,
+            day,
+        })
+    }
+
+    /// Days since 1970-01-01
+    fn to_days(self) -> i64 {
+        let year = if self.month <= 2 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = self.month as i64;
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + self.day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+        era * 146097 + day_of_era - 719468
+    }
+
+    fn from_days(days: i64) -> Self {
+        let days = days + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days - era * 146097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+        Self { year, month, day }
+    }
+
+    /// Day of the week, or `None` on weekends
+    fn weekday(self) -> Option<Day> {
+        // 1970-01-01 was a Thursday
+        match self.to_days().rem_euclid(7) {
+            0 => Some(Day::Thu),
+            1 => Some(Day::Fri),
+            4 => Some(Day::Mon),
+            5 => Some(Day::Tue),
+            6 => Some(Day::Wed),
+            _ => None,
+        }
+    }
+
+    fn format(self, time: Time) -> String {
+        format!(
+            "{:04}{:02}{:02}T{:02}{:02}00",
+            self.year, self.month, self.day, time.hour, time.minute
+        )
+    }
+}
+
+/// Parse the first and last day of a section, like `08/21/2023 - 12/05/2023`
+fn parse_dates(dates: &str) -> Option<(Date, Date)> {
+    let (start, end) = dates.split_once(" - ")?;
+
+    Some((Date::parse(start)?, Date::parse(end)?))
+}
+
+/// First day from `start` to `end` that falls on one of `days`
+fn first_day(start: Date, end: Date, days: &[Day]) -> Option<Date> {
+    (start.to_days()..=end.to_days())
+        .map(Date::from_days)
+        .find(|x| x.weekday().is_some_and(|x| days.contains(&x)))
+}
+
+fn rrule_day(day: Day) -> &'static str {
+    match day {
+        Day::Mon => "MO",
+        Day::Tue => "TU",
+        Day::Wed => "WE",
+        Day::Thu => "TH",
+        Day::Fri => "FR",
+    }
+}
+
+/// Escape a TEXT value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line to at most 75 octets per line
+fn fold(line: &str) -> String {
+    let mut res = String::new();
+    let mut len = 0;
+
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            res += "\r\n ";
+            len = 1;
+        }
+        res.push(c);
+        len += c.len_utf8();
+    }
+
+    res + "\r\n"
+}
+
+/// Write the classes of a schedule as weekly repeating events
+pub fn write_ics(mut writer: impl Write, schedule: &[&Section]) -> std::io::Result<()> {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let stamp = Date::from_days(now.div_euclid(86400))
+        .format(Time::from_min((now.rem_euclid(86400) / 60) as u32))
+        + "Z";
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//schedule-processor//EN".to_owned(),
+    ];
+
+    for section in schedule.iter().filter(|x| !x.busy) {
+        let Some((start, end)) = parse_dates(&section.dates) else {
+            eprintln!("Skipping {} {}: no dates", section.class, section.section);
+            continue;
+        };
+
+        // One event for each time the section meets at
+        let mut times: Vec<(TimeRange, Option<String>)> = Vec::new();
+        for meeting in &section.meetings {
+            let time = (meeting.time, meeting.building.clone());
+            if !times.contains(&time) {
+                times.push(time);
+            }
+        }
+
+        for (i, (time, building)) in times.into_iter().enumerate() {
+            let days: Vec<Day> = section
+                .meetings
+                .iter()
+                .filter(|x| x.time == time && x.building == building)
+                .map(|x| x.meet_day)
+                .collect();
+
+            let Some(first) = first_day(start, end, &days) else {
+                continue;
+            };
+
+            let location = [Some(section.facility.as_str()), building.as_deref()]
+                .into_iter()
+                .flatten()
+                .filter(|x| !x.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            lines.extend([
+                "BEGIN:VEVENT".to_owned(),
+                format!("UID:{}-{i}@schedule-processor", section.class_nbr),
+                format!("DTSTAMP:{stamp}"),
+                format!("DTSTART:{}", first.format(time.start)),
+                format!("DTEND:{}", first.format(time.end)),
+                format!(
+                    "RRULE:FREQ=WEEKLY;BYDAY={};UNTIL={}",
+                    days.iter()
+                        .map(|&x| rrule_day(x))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    end.format(Time {
+                        hour: 23,
+                        minute: 59
+                    })
+                ),
+                format!(
+                    "SUMMARY:{}",
+                    escape(&format!("{} {}", section.class, section.section))
+                ),
+                format!("LOCATION:{}", escape(&location)),
+                format!(
+                    "DESCRIPTION:{}",
+                    escape(&format!("Instructor: {}", section.professor))
+                ),
+                "END:VEVENT".to_owned(),
+            ]);
+        }
+    }
+
+    lines.push("END:VCALENDAR".to_owned());
+
+    for line in lines {
+        writer.write_all(fold(&line).as_bytes())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i64, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn days_round_trip() {
+        assert_eq!(date(1970, 1, 1).to_days(), 0);
+        assert_eq!(date(2000, 3, 1).to_days(), 11017);
+        assert_eq!(date(2023, 8, 21).to_days(), 19590);
+        assert_eq!(date(1969, 12, 31).to_days(), -1);
+
+        for date in [date(1970, 1, 1), date(2000, 2, 29), date(2023, 12, 5)] {
+            assert_eq!(Date::from_days(date.to_days()), date);
+        }
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(date(2023, 8, 21).weekday(), Some(Day::Mon));
+        assert_eq!(date(2023, 8, 22).weekday(), Some(Day::Tue));
+        assert_eq!(date(2023, 8, 25).weekday(), Some(Day::Fri));
+        assert_eq!(date(2023, 8, 26).weekday(), None);
+        assert_eq!(date(2023, 8, 27).weekday(), None);
+        assert_eq!(date(1970, 1, 1).weekday(), Some(Day::Thu));
+    }
+
+    #[test]
+    fn first_class_day() {
+        let (start, end) = parse_dates("08/21/2023 - 12/05/2023").unwrap();
+        assert_eq!(start, date(2023, 8, 21));
+        assert_eq!(end, date(2023, 12, 5));
+
+        assert_eq!(
+            first_day(start, end, &[Day::Tue, Day::Thu]),
+            Some(date(2023, 8, 22))
+        );
+        assert_eq!(first_day(start, end, &[Day::Mon]), Some(start));
+        assert_eq!(first_day(start, start, &[Day::Fri]), None);
+    }
+
+    #[test]
+    fn folds_multibyte_lines() {
+        let line = format!("SUMMARY:{}", "é".repeat(50));
+        let folded = fold(&line);
+
+        assert!(folded.contains("\r\n "));
+        assert!(folded.ends_with("\r\n"));
+        for part in folded.trim_end_matches("\r\n").split("\r\n") {
+            assert!(part.len() <= 75);
+        }
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+
+        assert_eq!(fold("VERSION:2.0"), "VERSION:2.0\r\n");
+    }
+
+    #[test]
+    fn escapes_text() {
+        assert_eq!(
+            escape("EB2 1231, Centennial; a\\b\nc"),
+            "EB2 1231\\, Centennial\\; a\\\\b\\nc"
+        );
+    }
+}
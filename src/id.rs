@@ -0,0 +1,86 @@
+use std::fmt::Display;
+
+use crate::Section;
+
+/// Sections of a schedule and what was computed about it
+type Schedule<'a, T> = (Vec<&'a Section>, T);
+
+/// Shortest ID prefix that is looked up before falling back to an index
+const MIN_PREFIX: usize = 4;
+
+/// FNV-1a, which unlike `DefaultHasher` is stable across runs and versions
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &x| {
+        (hash ^ x as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// ID of a schedule that only depends on the class numbers in it, so it stays
+/// the same when filters or sorting change
+pub fn schedule_id(schedule: &[&Section]) -> String {
+    let mut class_nbrs: Vec<&str> = schedule
+        .iter()
+        .filter(|x| !x.busy)
+        .map(|x| x.class_nbr.as_str())
+        .collect();
+    class_nbrs.sort();
+
+    let hash = fnv1a(class_nbrs.join(",").as_bytes());
+
+    format!("{:08x}", (hash ^ (hash >> 32)) as u32)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LookupError {
+    NotFound(String),
+    Ambiguous(String, usize),
+}
+
+impl Display for LookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LookupError::NotFound(query) => {
+                f.write_fmt(format_args!("No schedule matches {query}"))
+            }
+            LookupError::Ambiguous(query, count) => f.write_fmt(format_args!(
+                "{count} schedules start with {query}, use a longer ID"
+            )),
+        }
+    }
+}
+
+/// Find a schedule by ID prefix, or by index for short numbers
+pub fn find_schedule<T>(schedules: &[Schedule<T>], query: &str) -> Result<usize, LookupError> {
+    if query.len() >= MIN_PREFIX {
+        let found: Vec<usize> = schedules
+            .iter()
+            .enumerate()
+            .filter(|(_, (x, _))| schedule_id(x).starts_with(&query.to_lowercase()))
+            .map(|(i, _)| i)
+            .collect();
+
+        match found[..] {
+            [i] => return Ok(i),
+            [] => {}
+            _ => return Err(LookupError::Ambiguous(query.to_owned(), found.len())),
+        }
+    }
+
+    query
+        .parse()
+        .ok()
+        .filter(|&i| i < schedules.len())
+        .ok_or_else(|| LookupError::NotFound(query.to_owned()))
+}
+
+/// Find every schedule in `queries`, skipping `--` flags
+pub fn find_schedules<'s, T>(
+    schedules: &'s [Schedule<'s, T>],
+    queries: &[String],
+) -> Result<Vec<(usize, &'s Schedule<'s, T>)>, LookupError> {
+    queries
+        .iter()
+        .filter(|x| !x.starts_with("--"))
+        .map(|x| find_schedule(schedules, x).map(|i| (i, &schedules[i])))
+        .collect()
+}
@@ -1,14 +1,41 @@
-use plotters::prelude::*;
+use plotters::style::RGBColor;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::ops::Add;
 use std::ops::Sub;
 use std::str::FromStr;
 use std::{fs::File, io::BufReader};
 
+mod cart;
+mod constraints;
+mod crosslist;
 mod data;
+mod data_grade;
+mod dedupe;
+mod diff;
+mod grid;
+mod ics;
+mod id;
+mod metrics;
+mod pdf;
+mod render;
+mod report;
+mod requisite;
+mod travel;
+mod tui;
+use constraints::*;
+use crosslist::*;
 use data::*;
+use data_grade::*;
+use dedupe::*;
+use grid::*;
+use id::*;
+use metrics::*;
+use render::*;
+use requisite::*;
+use travel::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 enum Campus {
@@ -16,7 +43,7 @@ enum Campus {
     Centinnial,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 enum Day {
     Mon,
     Tue,
@@ -25,14 +52,18 @@ enum Day {
     Fri,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
+impl Day {
+    const ALL: [Day; 5] = [Day::Mon, Day::Tue, Day::Wed, Day::Thu, Day::Fri];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 struct Time {
     hour: u32,
     minute: u32,
 }
 
 impl Time {
-    fn into_min(&self) -> u32 {
+    fn into_min(self) -> u32 {
         self.hour * 60 + self.minute
     }
 
@@ -46,7 +77,13 @@ impl Time {
 
 impl Display for Time {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{}:{}", self.hour, self.minute))
+        f.write_fmt(format_args!("{}:{:02}", self.hour, self.minute))
+    }
+}
+
+impl PartialOrd for Time {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
@@ -87,6 +124,7 @@ trait Range {
     fn overlap(&self, other: &Self) -> bool;
 }
 
+// NOTE: Ranges which only touch (one ends when the other starts) overlap
 impl Range for TimeRange {
     fn overlap(&self, other: &Self) -> bool {
         self.start <= other.end && self.end >= other.start
@@ -103,7 +141,11 @@ impl Range for DayTime {
     fn overlap(&self, other: &Self) -> bool {
         self.meet_day.overlap(&other.meet_day)
             && self.time.overlap(&other.time)
-            && self.campus == other.campus
+            && match (self.campus, other.campus) {
+                (Some(this), Some(other)) => this == other,
+                // Without a location it's unknown where travel is needed
+                _ => true,
+            }
     }
 }
 
@@ -113,6 +155,12 @@ impl Range for Vec<DayTime> {
     }
 }
 
+impl Range for Credits {
+    fn overlap(&self, other: &Self) -> bool {
+        self.min <= other.max && self.max >= other.min
+    }
+}
+
 impl Range for Section {
     fn overlap(&self, other: &Self) -> bool {
         self.meetings.overlap(&other.meetings)
@@ -129,6 +177,14 @@ impl TimeRange {
     fn len(&self) -> u32 {
         self.end.into_min() - self.start.into_min()
     }
+
+    /// Minutes between the end of one range and the start of the other, negative if they overlap
+    fn gap(&self, other: &Self) -> i32 {
+        let after = other.start.into_min() as i32 - self.end.into_min() as i32;
+        let before = self.start.into_min() as i32 - other.end.into_min() as i32;
+
+        after.max(before)
+    }
 }
 
 impl Display for TimeRange {
@@ -137,6 +193,53 @@ impl Display for TimeRange {
     }
 }
 
+/// Credit hours, variable credit courses have `min < max`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+struct Credits {
+    min: u32,
+    max: u32,
+}
+
+impl Display for Credits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.min == self.max {
+            f.write_fmt(format_args!("{}", self.min))
+        } else {
+            f.write_fmt(format_args!("{}-{}", self.min, self.max))
+        }
+    }
+}
+
+impl FromStr for Credits {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = s.split_once('-').unwrap_or((s, s));
+
+        let min = min.trim().parse().map_err(|_| ())?;
+        let max = max.trim().parse().map_err(|_| ())?;
+
+        Ok(Self { min, max })
+    }
+}
+
+impl Add for Credits {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            min: self.min + rhs.min,
+            max: self.max + rhs.max,
+        }
+    }
+}
+
+impl std::iter::Sum for Credits {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Credits::default(), Add::add)
+    }
+}
+
 impl Add<i32> for Time {
     type Output = Self;
 
@@ -169,13 +272,28 @@ impl Sub<u32> for Time {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
 struct Rating {
+    /// Share of A grades, from 0 to 1
     percent_a: f64,
     count_a: u64,
     count: u64,
 }
 
+impl Rating {
+    /// Key of a professor in the ratings, so `Doe, Jane` and `Jane Doe` match
+    fn key(name: &str) -> String {
+        let mut words: Vec<String> = name
+            .split(|c: char| !c.is_alphabetic())
+            .filter(|x| !x.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        words.sort();
+
+        words.join(" ")
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct Section {
     class: String,
@@ -186,13 +304,94 @@ struct Section {
     meetings: Vec<DayTime>,
 
     professor: String,
+
+    #[serde(default)]
+    credits: Credits,
+
+    #[serde(default)]
+    class_nbr: String,
+
+    /// Whether this is a personal commitment rather than a class
+    #[serde(default)]
+    busy: bool,
+
+    /// First and last day of class, like `08/21/2023 - 12/05/2023`
+    #[serde(default)]
+    dates: String,
+
+    /// What the registration system needs to add this section to the shopping cart
+    #[serde(default)]
+    cart: Vec<AddToCartDatum>,
+
+    /// Codes of the same course in other departments
+    #[serde(default)]
+    cross_listed: Vec<String>,
+
+    /// Class numbers of the same meeting under a cross-listed code
+    #[serde(default)]
+    aliases: Vec<String>,
+
+    /// Position of the course in the loaded course list, after merging cross-listed ones
+    #[serde(default)]
+    course: usize,
+
+    /// Whether the registration system says the requisites are met, if it knows
+    #[serde(default)]
+    requisite_met: Option<bool>,
+
+    /// Requisites as written in the catalog, like `Prerequisite: CSC 216 and CSC 226`
+    #[serde(default)]
+    requisites: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct DayTime {
     meet_day: Day,
     time: TimeRange,
-    campus: Campus,
+    campus: Option<Campus>,
+    #[serde(default)]
+    building: Option<String>,
+}
+
+/// A recurring commitment like work, practice or a club meeting
+#[derive(Debug, Clone, Copy)]
+struct Block {
+    name: &'static str,
+    days: &'static [Day],
+    time: TimeRange,
+    /// Where the commitment is, if travel to and from it matters
+    campus: Option<Campus>,
+}
+
+impl From<&Block> for Section {
+    fn from(block: &Block) -> Self {
+        Section {
+            class: block.name.to_owned(),
+            section: String::new(),
+            facility: String::new(),
+            meetings: block
+                .days
+                .iter()
+                .map(|&meet_day| DayTime {
+                    meet_day,
+                    time: block.time,
+                    campus: block.campus,
+                    building: None,
+                })
+                .collect(),
+            professor: String::new(),
+            credits: Credits::default(),
+            class_nbr: String::new(),
+            busy: true,
+            dates: String::new(),
+            cart: Vec::new(),
+            cross_listed: Vec::new(),
+            aliases: Vec::new(),
+            course: 0,
+            requisite_met: None,
+            requisites: String::new(),
+        }
+    }
 }
 
 // const FILES_NAMES: &[&str; 5] = &[
@@ -211,22 +410,367 @@ struct DayTime {
 // ];
 
 const FILES_NAMES: &[&str; 3] = &["csc246", "csc326", "csc379"];
+// Courses that are taken only if they fit, see CREDITS
+const OPTIONAL_FILES_NAMES: &[&str; 0] = &[];
+// Exactly n courses out of each group are taken
+// const GROUPS: &[(&[&str], usize)] = &[(&["ma305", "ma341"], 1)];
+const GROUPS: &[(&[&str], usize)] = &[];
+// Sections already registered for, by class number or section code
+// const PINNED: &[(&str, &str)] = &[("csc246", "001"), ("csc326", "12345")];
+const PINNED: &[(&str, &str)] = &[];
+// Times that no class may be scheduled during
+// const BLOCKS: &[Block] = &[Block {
+//     name: "Payload Meeting",
+//     days: &[Day::Wed],
+//     time: TimeRange {
+//         start: Time {
+//             hour: 12 + 3,
+//             minute: 0,
+//         },
+//         end: Time {
+//             hour: 12 + 4,
+//             minute: 0,
+//         },
+//     },
+//     campus: None,
+// }];
+const BLOCKS: &[Block] = &[];
+
+const CREDITS: Credits = Credits { min: 0, max: 18 };
+
+// Courses already taken, checked against the parsed prerequisites when not empty
+// const COMPLETED: &[&str] = &["CSC 216", "CSC 226", "MA 241"];
+const COMPLETED: &[&str] = &[];
+// Drop sections whose requisites are not met instead of only warning about them
+const EXCLUDE_UNMET: bool = false;
+// Classes that have to be taken together, on top of the parsed corequisites
+// const COREQUISITES: &[(&str, &str)] = &[("PY 208", "PY 209"), ("PY 209", "PY 208")];
+const COREQUISITES: &[(&str, &str)] = &[];
+
+// Where exported schedules are written
+const EXPORT_DIR: &str = "exports";
+
+// Size of each drawn schedule, in pixels
+const IMAGE_SIZE: (u32, u32) = (640, 480);
+// Number of the best schedules that go into the contact sheet and PDF
+const TOP_COUNT: usize = 12;
+
+const THEME: Theme = Theme {
+    title_font: "Arial",
+    font: "sans-serif",
+    font_size: 15,
+    background: RGBColor(255, 255, 255),
+    palette: &[
+        RGBColor(150, 150, 200),
+        RGBColor(200, 150, 150),
+        RGBColor(150, 200, 150),
+        RGBColor(210, 190, 130),
+        RGBColor(180, 150, 200),
+        RGBColor(130, 190, 200),
+        RGBColor(220, 170, 120),
+        RGBColor(200, 160, 190),
+    ],
+    busy: RGBColor(200, 200, 200),
+    // Some((8, 8 + 11))
+    hours: None,
+    legend: true,
+    highlight: RGBColor(220, 40, 40),
+};
+
+const PASSING_TIME: PassingTime = PassingTime {
+    same_building: 0,
+    same_campus: 0,
+    different_campus: 30,
+    boundary: Boundary::Inclusive,
+};
+
+// Order of the schedules, best first
+fn sort_key(metrics: &Metrics) -> impl Ord {
+    (metrics.days, metrics.idle)
+}
+
+// Schedules to keep after solving
+fn keep(_metrics: &Metrics) -> bool {
+    // No classes before 9
+    // metrics.bounds.iter().all(|(_, x)| x.start.hour >= 9)
+    true
+}
+
+// Constraints on the whole schedule, and whether each starts enabled
+fn constraints() -> Vec<(bool, Box<dyn Constraint>)> {
+    vec![
+        (
+            false,
+            Box::new(MealBreak {
+                window: TimeRange {
+                    start: Time {
+                        hour: 11,
+                        minute: 0,
+                    },
+                    end: Time {
+                        hour: 12 + 2,
+                        minute: 0,
+                    },
+                },
+                length: 30,
+            }),
+        ),
+        (false, Box::new(MaxDailyMinutes(5 * 60))),
+        (
+            false,
+            Box::new(MaxConsecutive {
+                minutes: 3 * 60,
+                break_length: 30,
+            }),
+        ),
+        (false, Box::new(MaxDays(4))),
+        (false, Box::new(MaxGaps(6 * 60))),
+        // Schedules missing a corequisite are listed, enable to drop them instead
+        (
+            false,
+            Box::new(Corequisites {
+                rules: COREQUISITES,
+                completed: COMPLETED,
+            }),
+        ),
+    ]
+}
+
+/// What a schedule has to contain to be a solution
+struct Requirements {
+    /// Whether the course at the same index must be in every schedule
+    required: Vec<bool>,
+    groups: Vec<Group>,
+    /// The allowed range of total credit hours
+    credits: Credits,
+    /// Constraints on the whole schedule, only enabled ones are checked
+    constraints: Vec<(bool, Box<dyn Constraint>)>,
+}
+
+/// Courses (by index) of which exactly `count` must be taken
+#[derive(Debug, Clone)]
+struct Group {
+    courses: Vec<usize>,
+    count: usize,
+}
+
+impl Group {
+    /// Whether the courses decided so far can still satisfy this group
+    fn feasible(&self, history: &[Option<usize>]) -> bool {
+        let taken = self
+            .courses
+            .iter()
+            .filter(|&&i| matches!(history.get(i), Some(Some(_))))
+            .count();
+        let undecided = self.courses.iter().filter(|&&i| i >= history.len()).count();
+
+        taken <= self.count && taken + undecided >= self.count
+    }
+}
+
+#[derive(Debug)]
+enum PinError {
+    /// No section of the course has the pinned class number or section code
+    NotFound { class: String, pin: String },
+    /// The pinned section was removed by a filter
+    Filtered { class: String, pin: String },
+    /// Two pinned sections can't be taken together
+    Conflict { this: String, other: String },
+    /// Two pins are for the same course, through a cross-listed code
+    Duplicate {
+        class: String,
+        this: String,
+        other: String,
+    },
+}
+
+impl Display for PinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PinError::NotFound { class, pin } => {
+                f.write_fmt(format_args!("{class} has no section matching {pin}"))
+            }
+            PinError::Filtered { class, pin } => {
+                f.write_fmt(format_args!("{class} {pin} is excluded by the filters"))
+            }
+            PinError::Conflict { this, other } => {
+                f.write_fmt(format_args!("{this} can't be taken with {other}"))
+            }
+            PinError::Duplicate { class, this, other } => f.write_fmt(format_args!(
+                "{class} is pinned to both {this} and {other}, but can only be taken once"
+            )),
+        }
+    }
+}
+
+impl std::error::Error for PinError {}
+
+/// Reduce each pinned course to only its pinned section
+fn pin(classes: &mut [Vec<Section>], pins: &[(usize, &str)]) -> Result<(), PinError> {
+    for (i, &(index, pin)) in pins.iter().enumerate() {
+        let course = &mut classes[index];
+        let class = course.first().map(|x| x.class.clone()).unwrap_or_default();
+
+        if let Some(&(_, other)) = pins[..i].iter().find(|(x, _)| *x == index) {
+            return Err(PinError::Duplicate {
+                class,
+                this: other.to_owned(),
+                other: pin.to_owned(),
+            });
+        }
+
+        course.retain(|x| {
+            x.class_nbr == pin || x.aliases.iter().any(|x| x == pin) || x.section == pin
+        });
+
+        if course.is_empty() {
+            return Err(PinError::NotFound {
+                class,
+                pin: pin.to_owned(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Make sure the pinned sections survived filtering and fit together and
+/// around the blocked times
+fn check_pins(
+    classes: &[Vec<Section>],
+    files: &[&str],
+    pins: &[(usize, &str)],
+    blocks: &[Block],
+) -> Result<(), PinError> {
+    for &(index, pin) in pins {
+        if classes[index].is_empty() {
+            return Err(PinError::Filtered {
+                class: files[index].to_owned(),
+                pin: pin.to_owned(),
+            });
+        }
+    }
+
+    for (i, &(this, _)) in pins.iter().enumerate() {
+        for &(other, _) in &pins[i + 1..] {
+            let this = &classes[this][0];
+            let other = &classes[other][0];
+
+            if !practical(this, other) {
+                return Err(PinError::Conflict {
+                    this: format!("{} {}", this.class, this.section),
+                    other: format!("{} {}", other.class, other.section),
+                });
+            }
+        }
+    }
+
+    for &(index, _) in pins {
+        let this = &classes[index][0];
+
+        for block in blocks {
+            if !practical(this, &block.into()) {
+                return Err(PinError::Conflict {
+                    this: format!("{} {}", this.class, this.section),
+                    other: block.name.to_owned(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
 
 fn main() {
-    let mut classes: Vec<Vec<Section>> = FILES_NAMES.iter().map(|&file| class_data(file)).collect();
+    let mut files: Vec<&str> = Vec::new();
+    for &file in FILES_NAMES
+        .iter()
+        .chain(OPTIONAL_FILES_NAMES.iter())
+        .chain(GROUPS.iter().flat_map(|(group, _)| group.iter()))
+        .chain(PINNED.iter().map(|(file, _)| file))
+    {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+
+    let mut classes: Vec<Vec<Section>> = files.iter().map(|&file| class_data(file)).collect();
+
+    // Cross-listed courses become one course, so they can't be taken twice
+    let courses = merge_cross_listed(&mut classes);
+    for (i, course) in classes.iter_mut().enumerate() {
+        for section in course {
+            section.course = i;
+        }
+    }
+    let index = |file: &str| courses[files.iter().position(|&x| x == file).unwrap()];
+    // File of each course after merging
+    let names: Vec<&str> = (0..classes.len())
+        .map(|i| *files.iter().find(|&&x| index(x) == i).unwrap())
+        .collect();
+
+    let pins: Vec<(usize, &str)> = PINNED
+        .iter()
+        .map(|&(file, pin)| (index(file), pin))
+        .collect();
+
+    if let Err(err) = pin(&mut classes, &pins) {
+        eprintln!("Invalid pin: {err}");
+        return;
+    }
+
+    let mut requirements = Requirements {
+        required: (0..classes.len())
+            .map(|i| {
+                files
+                    .iter()
+                    .filter(|&&x| index(x) == i)
+                    .any(|file| FILES_NAMES.contains(file) || PINNED.iter().any(|(x, _)| x == file))
+            })
+            .collect(),
+        groups: GROUPS
+            .iter()
+            .map(|&(group, count)| {
+                let mut courses: Vec<usize> = group.iter().map(|&file| index(file)).collect();
+                courses.sort();
+                courses.dedup();
+
+                Group { courses, count }
+            })
+            .collect(),
+        credits: CREDITS,
+        constraints: constraints(),
+    };
 
     let mut acc = 1;
 
-    for v in classes.iter() {
-        acc *= v.len();
-        println!("{:?}: {:?}", v[0].class, v.len());
+    for (i, v) in classes.iter().enumerate() {
+        println!("{:?}: {:?}", names[i], v.len());
+
+        // Optional courses with no sections left are just skipped
+        if !v.is_empty() || requirements.required[i] {
+            acc *= v.len();
+        }
+    }
+
+    for (class, requisite) in unavailable_corequisites(&classes, COREQUISITES, COMPLETED) {
+        println!("{class}: corequisite {requisite} is not in any loaded file or completed");
+    }
+
+    for x in classes.iter().flatten() {
+        if !requisites_met(x, COMPLETED) {
+            println!(
+                "{} {}: requisites not met ({})",
+                x.class, x.section, x.requisites
+            );
+        }
     }
 
     // NOTE: Filter
     for v in classes.iter_mut() {
         v.retain(|x| {
             // No online classes
-            if x.meetings.len() == 0 {
+            if x.meetings.is_empty() {
                 return false;
             }
 
@@ -279,140 +823,250 @@ fn main() {
             let fri = x.meetings.iter().any(|m| m.meet_day == Day::Fri);
             // let fri = false;
 
-            !(late || early || fri)
+            // Requisites not met
+            let unmet = EXCLUDE_UNMET && !requisites_met(x, COMPLETED);
+
+            !(late || early || fri || unmet)
         })
     }
 
+    if let Err(err) = check_pins(&classes, &names, &pins, BLOCKS) {
+        eprintln!("Pinned sections are infeasible: {err}");
+        return;
+    }
+
     println!("After filter");
-    for v in classes.iter() {
-        acc *= v.len();
-        println!("{:?}: {:?}", v[0].class, v.len());
+    for (i, v) in classes.iter().enumerate() {
+        println!("{:?}: {:?}", names[i], v.len());
+
+        // Optional courses with no sections left are just skipped
+        if !v.is_empty() || requirements.required[i] {
+            acc *= v.len();
+        }
     }
 
     println!("Total possible {acc}");
 
-    let solutions = possible_schedules(&classes);
+    // Blocks are added after filtering so they are always in the schedule
+    for block in BLOCKS {
+        classes.push(vec![block.into()]);
+        requirements.required.push(true);
+    }
 
-    if solutions.len() == 0 {
-        eprintln!("No solutions found!");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = args.first().map(String::as_str);
+    let format = if args.iter().any(|x| x == "--svg") {
+        Format::Svg
     } else {
-        println!(
-            "{} Solutions found",
-            solutions.iter().flat_map(Tree::all_paths).count()
-        );
-        draw_classes(&solutions).unwrap()
-    }
-}
-
-fn draw_classes(solutions: &Vec<Tree>) -> Result<(), Box<dyn std::error::Error>> {
-    _ = std::fs::create_dir("output_images");
-
-    for (i, data) in solutions.iter().flat_map(Tree::all_paths).enumerate() {
-        let name = format!("output_images/{i}.png");
-        let root = BitMapBackend::new(&name, (640, 480)).into_drawing_area();
-        root.fill(&WHITE)?;
-
-        let w_count = 5;
-        let h_count = 11;
-        let h_start = 8;
-
-        // The following code will create a chart context
-        let mut chart = ChartBuilder::on(&root)
-            .caption("Schedule", ("Arial", 20).into_font())
-            .x_label_area_size(40)
-            .y_label_area_size(40)
-            .build_cartesian_2d(
-                0.5f32..(w_count as f32 + 0.5) as f32,
-                ((h_start + h_count) as f32)..h_start as f32,
-            )?;
-
-        chart
-            .configure_mesh()
-            .y_labels(h_count + 1)
-            .y_label_formatter(&|&x| {
-                let am = x <= 12.0;
-                format!(
-                    "{:0.} {}",
-                    if am { x } else { x - 12.0 },
-                    if am { "AM" } else { "PM" }
-                )
-            })
-            .x_label_formatter(&|&x| {
-                if x.fract() != 0.0 {
-                    return "".to_string();
-                }
+        Format::Png
+    };
+
+    if command == Some("tui") {
+        tui::run(&classes, &mut requirements).unwrap();
+        return;
+    }
+
+    let mut schedules = solve(&classes, &requirements);
+    // Collapse schedules that only differ by interchangeable sections
+    let alternatives = if args.iter().any(|x| x == "--dedupe") {
+        dedupe(&mut schedules)
+    } else {
+        Vec::new()
+    };
+
+    if schedules.is_empty() {
+        eprintln!("No solutions found!");
+    } else if command == Some("ics") {
+        // ics (id | index)
+        let Some(query) = args.get(1) else {
+            eprintln!("Usage: ics <schedule id or index>");
+            return;
+        };
+        let (data, _) = match find_schedule(&schedules, query) {
+            Ok(i) => &schedules[i],
+            Err(err) => {
+                eprintln!("{err}");
+                return;
+            }
+        };
+        let id = schedule_id(data);
+
+        _ = std::fs::create_dir(EXPORT_DIR);
+        let name = format!("{EXPORT_DIR}/{id}.ics");
+        ics::write_ics(File::create(&name).unwrap(), data).unwrap();
+        println!("Wrote {name}");
+    } else if command == Some("cart") {
+        // cart (id | index) [--csv]
+        let Some(query) = args.get(1) else {
+            eprintln!("Usage: cart <schedule id or index> [--csv]");
+            return;
+        };
+        let (data, _) = match find_schedule(&schedules, query) {
+            Ok(i) => &schedules[i],
+            Err(err) => {
+                eprintln!("{err}");
+                return;
+            }
+        };
+        let id = schedule_id(data);
+
+        _ = std::fs::create_dir(EXPORT_DIR);
+        let name = if args.iter().any(|x| x == "--csv") {
+            let name = format!("{EXPORT_DIR}/{id}-cart.csv");
+            cart::write_cart_csv(File::create(&name).unwrap(), data).unwrap();
+            name
+        } else {
+            let name = format!("{EXPORT_DIR}/{id}-cart.json");
+            cart::write_cart_json(File::create(&name).unwrap(), data).unwrap();
+            name
+        };
+        println!("Wrote {name}");
+
+        let class_nbrs: Vec<&str> = data
+            .iter()
+            .filter(|x| !x.busy)
+            .map(|x| x.class_nbr.as_str())
+            .collect();
+        println!("Class numbers: {}", class_nbrs.join(", "));
+    } else if command == Some("diff") {
+        // diff (id | index) (id | index) [--svg]
+        let chosen = match find_schedules(&schedules, &args[1..]) {
+            Ok(chosen) => chosen,
+            Err(err) => {
+                eprintln!("{err}");
+                return;
+            }
+        };
+        let [(_, (a, _)), (_, (b, _))] = chosen[..] else {
+            eprintln!("Usage: diff <schedule id or index> <schedule id or index> [--svg]");
+            return;
+        };
 
-                match x as i32 {
-                    1 => "Mon",
-                    2 => "Tue",
-                    3 => "Wed",
-                    4 => "Thu",
-                    5 => "Fri",
-                    _ => "",
+        let changes = diff::diff(a, b);
+        if changes.is_empty() {
+            println!(
+                "Schedules {} and {} are the same",
+                schedule_id(a),
+                schedule_id(b)
+            );
+        }
+        for change in &changes {
+            println!("{change}");
+        }
+
+        let marked: Vec<&Section> = changes.iter().flat_map(|x| x.sections()).collect();
+        draw_comparison("diff", &chosen, &marked, format, IMAGE_SIZE, &THEME).unwrap();
+    } else if command == Some("report") {
+        _ = std::fs::create_dir(EXPORT_DIR);
+        let name = format!("{EXPORT_DIR}/report.html");
+        report::write_report(
+            File::create(&name).unwrap(),
+            &schedules,
+            &ratings(&files),
+            COMPLETED,
+            IMAGE_SIZE,
+            &THEME,
+        )
+        .unwrap();
+        println!("Wrote {name}");
+    } else if command == Some("grid") {
+        // grid [id | index] [--ascii]
+        let charset = if args.iter().any(|x| x == "--ascii") {
+            Charset::Ascii
+        } else {
+            Charset::Unicode
+        };
+        let index = match args.iter().skip(1).find(|x| !x.starts_with("--")) {
+            Some(query) => match find_schedule(&schedules, query) {
+                Ok(i) => Some(i),
+                Err(err) => {
+                    eprintln!("{err}");
+                    return;
                 }
-                .to_string()
-            })
-            .light_line_style(&TRANSPARENT)
-            .disable_x_mesh()
-            .draw()?;
-
-        let (w, h) = chart.plotting_area().dim_in_pixel();
-        let size = |len| {
-            let width = w as i32 / (w_count);
-            let height = h as f32 / (h_count as f32) * len;
-            [(-width / 2, 0), (width / 2, height as i32)]
+            },
+            None => None,
         };
 
-        chart.draw_series(data.iter().flat_map(|x| {
-            x.meetings.iter().map(|meeting| {
-                let size = size(meeting.time.len() as f32 / 60.);
-
-                let pos_x = match meeting.meet_day {
-                    Day::Mon => 1.0,
-                    Day::Tue => 2.0,
-                    Day::Wed => 3.0,
-                    Day::Thu => 4.0,
-                    Day::Fri => 5.0,
-                };
-
-                let pos_y = meeting.time.start.hour as f32 + meeting.time.start.minute as f32 / 60.;
-
-                let color = match meeting.campus {
-                    Campus::North => RGBColor(200, 150, 150),
-                    Campus::Centinnial => RGBColor(150, 150, 200),
-                };
-
-                EmptyElement::at((pos_x, pos_y))
-                    + Rectangle::new(size, color.filled())
-                    + Rectangle::new(size, RGBColor(255, 255, 255).stroke_width(2))
-                    + Text::new(
-                        format!("{} {}", x.class, x.section),
-                        (size[0].0 + 2, size[0].1 + 2),
-                        ("sans-serif", 15).into_font(),
-                    )
-                    + Text::new(
-                        x.professor.split(' ').next().unwrap().to_owned(),
-                        (size[0].0 + 2, size[0].1 + 2 + 15),
-                        ("sans-serif", 15).into_font(),
-                    )
-            })
-        }))?;
+        for (i, (data, _)) in schedules.iter().enumerate() {
+            if index.is_none_or(|index| index == i) {
+                println!("Schedule {i} ({})", schedule_id(data));
+                for line in week_grid(data, terminal_width(), charset) {
+                    println!("{line}");
+                }
+                println!();
+            }
+        }
+    } else {
+        println!("{} Solutions found", schedules.len());
+
+        print_table(&schedules);
+        print_alternatives(&schedules, &alternatives);
+
+        for (data, _) in &schedules {
+            for transition in transitions(data, &PASSING_TIME) {
+                if transition.tight() {
+                    println!("{}: Tight transition {transition}", schedule_id(data));
+                }
+            }
+
+            for (class, requisite) in missing_corequisites(data, COREQUISITES, COMPLETED) {
+                println!(
+                    "{}: {class} is missing its corequisite {requisite}",
+                    schedule_id(data)
+                );
+            }
+        }
+
+        // [sheet | pdf | compare (id | index)...] [--svg]
+        match command {
+            Some("compare") => match find_schedules(&schedules, &args[1..]) {
+                Ok(chosen) if chosen.is_empty() => {
+                    eprintln!("Usage: compare <schedule id or index>... [--svg]")
+                }
+                Ok(chosen) => {
+                    draw_comparison("compare", &chosen, &[], format, IMAGE_SIZE, &THEME).unwrap()
+                }
+                Err(err) => eprintln!("{err}"),
+            },
+            Some("sheet") => draw_sheet(&schedules, format, IMAGE_SIZE, TOP_COUNT, &THEME).unwrap(),
+            Some("pdf") => draw_pdf(&schedules, IMAGE_SIZE, TOP_COUNT, &THEME).unwrap(),
+            _ => draw_classes(&schedules, format, IMAGE_SIZE, &THEME).unwrap(),
+        }
     }
-    Ok(())
+}
+
+/// Every schedule that meets the requirements and `keep`, sorted by `sort_key`
+fn solve<'a>(
+    classes: &'a Vec<Vec<Section>>,
+    requirements: &Requirements,
+) -> Vec<(Vec<&'a Section>, Metrics)> {
+    let mut schedules: Vec<(Vec<&Section>, Metrics)> = possible_schedules(classes, requirements)
+        .iter()
+        .flat_map(Tree::all_paths)
+        .map(|x| {
+            let metrics = Metrics::new(&x, &PASSING_TIME);
+            (x, metrics)
+        })
+        .filter(|(_, metrics)| keep(metrics))
+        .collect();
+    schedules.sort_by_key(|(_, metrics)| sort_key(metrics));
+
+    schedules
 }
 
 #[derive(Debug)]
 struct Tree<'a> {
-    value: &'a Section,
+    /// `None` when the course was left out of the schedule
+    value: Option<&'a Section>,
     children: Vec<Tree<'a>>,
 }
 
-impl Tree<'_> {
+impl<'a> Tree<'a> {
     fn depth(&self) -> usize {
         let mut count = 0;
         let mut curr = self;
 
-        while curr.children.len() > 0 {
+        while !curr.children.is_empty() {
             count += 1;
             curr = &curr.children[0];
         }
@@ -420,7 +1074,7 @@ impl Tree<'_> {
         count
     }
 
-    fn all_paths(&self) -> AllPaths {
+    fn all_paths(&self) -> AllPaths<'_, 'a> {
         AllPaths {
             tree: self,
             current: vec![0; self.depth()],
@@ -429,12 +1083,12 @@ impl Tree<'_> {
 }
 
 #[derive(Debug)]
-struct AllPaths<'a> {
-    tree: &'a Tree<'a>,
+struct AllPaths<'t, 'a> {
+    tree: &'t Tree<'a>,
     current: Vec<usize>,
 }
 
-impl<'a> Iterator for AllPaths<'a> {
+impl<'a> Iterator for AllPaths<'_, 'a> {
     type Item = Vec<&'a Section>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -449,12 +1103,12 @@ impl<'a> Iterator for AllPaths<'a> {
 
         let mut curr = self.tree;
         for &x in &self.current {
-            res.push(curr.value);
+            res.extend(curr.value);
             lens.push(curr.children.len());
 
             curr = &curr.children[x];
         }
-        res.push(curr.value);
+        res.extend(curr.value);
         lens.push(curr.children.len());
 
         // Add one with overflow
@@ -474,23 +1128,45 @@ impl<'a> Iterator for AllPaths<'a> {
 
 fn possible_schedules_recursive<'a>(
     courses: &'a Vec<Vec<Section>>,
-    history: &mut Vec<usize>,
+    requirements: &Requirements,
+    history: &mut Vec<Option<usize>>,
 ) -> Option<Vec<Tree<'a>>> {
+    let chosen: Vec<&Section> = history
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &j)| j.map(|j| &courses[i][j]))
+        .collect();
+    let credits: Credits = chosen.iter().map(|x| x.credits).sum();
+
     let index = history.len();
     if index == courses.len() {
         // base case
-        return Some(Vec::new());
+        return if credits.overlap(&requirements.credits)
+            && requirements
+                .constraints
+                .iter()
+                .all(|(enabled, x)| !enabled || x.complete(&chosen))
+        {
+            Some(Vec::new())
+        } else {
+            None
+        };
     }
 
     let res: Vec<_> = courses[index]
         .iter()
         .enumerate()
-        .filter(|(_, v)| {
-            history
+        .filter(|(_, v)| (credits + v.credits).min <= requirements.credits.max)
+        .filter(|(_, v)| chosen.iter().all(|other| practical(v, other)))
+        .filter(|&(_, v)| {
+            requirements
+                .constraints
                 .iter()
-                .enumerate()
-                .all(|(i, &j)| practical(&v, &courses[i][j]))
+                .all(|(enabled, x)| !enabled || x.partial(&chosen, v))
         })
+        .map(|(i, v)| (Some(i), Some(v)))
+        // Optional courses can also be skipped
+        .chain((!requirements.required[index]).then_some((None, None)))
         .collect();
 
     // TODO: Ideally this wouldn't be required
@@ -499,7 +1175,11 @@ fn possible_schedules_recursive<'a>(
         .into_iter()
         .filter_map(|(i, v)| {
             history.push(i);
-            let res = possible_schedules_recursive(courses, history);
+            let res = if requirements.groups.iter().all(|x| x.feasible(history)) {
+                possible_schedules_recursive(courses, requirements, history)
+            } else {
+                None
+            };
             assert_eq!(Some(i), history.pop());
 
             res.map(|res| Tree {
@@ -509,37 +1189,22 @@ fn possible_schedules_recursive<'a>(
         })
         .collect();
 
-    if res.len() == 0 {
+    if res.is_empty() {
         None
     } else {
         Some(res)
     }
 }
 
-fn possible_schedules<'a>(courses: &'a Vec<Vec<Section>>) -> Vec<Tree<'a>> {
-    possible_schedules_recursive(courses, &mut Vec::new()).unwrap()
+fn possible_schedules<'a>(
+    courses: &'a Vec<Vec<Section>>,
+    requirements: &Requirements,
+) -> Vec<Tree<'a>> {
+    possible_schedules_recursive(courses, requirements, &mut Vec::new()).unwrap_or_default()
 }
 
 fn practical(this: &Section, other: &Section) -> bool {
-    let overlap = this.overlap(other);
-
-    let campus_travel = {
-        this.meetings.iter().any(|x| {
-            let expanded_range = TimeRange {
-                start: x.time.start - 30,
-                end: x.time.end + 30,
-            };
-
-            other
-                .meetings
-                .iter()
-                .filter(|y| y.meet_day == x.meet_day)
-                .filter(|y| y.time.overlap(&expanded_range))
-                .any(|y| x.campus != y.campus)
-        })
-    };
-
-    !(overlap || campus_travel)
+    travel::feasible(&[this, other], &PASSING_TIME)
 }
 
 #[allow(unused)]
@@ -582,7 +1247,7 @@ fn class_data(file_name: &str) -> Vec<Section> {
 
     let data: Vec<Section> = data
         .into_iter()
-        .filter_map(|data| {
+        .map(|data| {
             let meetings = data
                 .section_details
                 .iter()
@@ -612,9 +1277,9 @@ fn class_data(file_name: &str) -> Vec<Section> {
                     };
 
                     let campus = if section.location.contains("North") {
-                        Campus::North
+                        Some(Campus::North)
                     } else {
-                        Campus::Centinnial
+                        Some(Campus::Centinnial)
                     };
 
                     // NOTE: This assumes every class is at the same time
@@ -625,12 +1290,15 @@ fn class_data(file_name: &str) -> Vec<Section> {
                         end: calendar_info.end_time.parse().unwrap(),
                     };
 
+                    let building = Some(section.building_address.clone()).filter(|x| !x.is_empty());
+
                     meet_days
                         .iter()
-                        .map(move |&meet_day| DayTime {
+                        .map(|&meet_day| DayTime {
                             meet_day,
                             time,
                             campus,
+                            building: building.clone(),
                         })
                         .collect::<Vec<_>>()
                 })
@@ -643,15 +1311,64 @@ fn class_data(file_name: &str) -> Vec<Section> {
                 .unwrap_or(&String::from(""))
                 .clone();
 
-            Some(Section {
+            let cross_listed = parse_course_codes(&data.xlist_descr)
+                .into_iter()
+                .filter(|x| *x != data.classs)
+                .collect();
+
+            Section {
                 class: data.classs,
                 section: data.section_details[0].section.clone(),
                 facility: data.section_details[0].facility.clone(),
                 meetings,
                 professor,
-            })
+                credits: data.units.parse().unwrap_or_default(),
+                class_nbr: data.section_details[0].class_nbr.clone(),
+                busy: false,
+                dates: data.section_details[0].dates.clone(),
+                cart: data.add_to_cart_data,
+                cross_listed,
+                aliases: Vec::new(),
+                course: 0,
+                requisite_met: match data.requisite_met.as_str() {
+                    "Y" => Some(true),
+                    "N" => Some(false),
+                    _ => None,
+                },
+                requisites: data.requisite_descr,
+            }
         })
         .collect();
 
     data
 }
+
+/// Grade distributions for a course, which don't have to exist
+fn grade_data(file_name: &str) -> Vec<Individual> {
+    let Ok(grade_file) = File::open(format! {"data/grade/{file_name}.json"}) else {
+        return Vec::new();
+    };
+    let reader = BufReader::new(grade_file);
+    let root: GradeRoot = serde_json::from_reader(reader).unwrap();
+
+    root.individual
+}
+
+/// Ratings of every professor in the grade data of `files`, by `Rating::key`
+fn ratings(files: &[&str]) -> HashMap<String, Rating> {
+    let mut ratings: HashMap<String, Rating> = HashMap::new();
+
+    for individual in files.iter().flat_map(|file| grade_data(file)) {
+        let rating = ratings
+            .entry(Rating::key(&individual.instructor_name))
+            .or_default();
+        rating.count_a += individual.grades.a.raw.max(0) as u64;
+        rating.count += individual.grades.total.raw.max(0) as u64;
+    }
+
+    for rating in ratings.values_mut() {
+        rating.percent_a = rating.count_a as f64 / rating.count.max(1) as f64;
+    }
+
+    ratings
+}
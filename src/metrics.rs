@@ -0,0 +1,96 @@
+use crate::constraints::{class_days, day_times, gaps, stretches};
+use crate::id::schedule_id;
+use crate::travel::{transitions, PassingTime};
+use crate::{Credits, Day, Section, Time, TimeRange};
+
+/// Shortest time between classes that counts as a break, in minutes
+const BREAK_LENGTH: u32 = 30;
+
+/// How spread out a schedule is over the week
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    pub credits: Credits,
+    /// Minutes between classes over the whole week
+    pub idle: u32,
+    /// Number of days with class
+    pub days: usize,
+    /// Start of the first class and end of the last class on each day with class
+    pub bounds: Vec<(Day, TimeRange)>,
+    /// Longest time in class without a break, in minutes
+    pub longest_stretch: u32,
+    /// Number of transitions between meetings with little or no time to spare
+    pub tight: usize,
+}
+
+impl Metrics {
+    pub fn new(schedule: &[&Section], passing: &PassingTime) -> Self {
+        let classes: Vec<&Section> = schedule.iter().filter(|x| !x.busy).copied().collect();
+
+        let mut days = class_days(&classes);
+        days.sort_by_key(|&x| x as u8);
+
+        let times: Vec<(Day, Vec<TimeRange>)> = days
+            .iter()
+            .map(|&day| (day, day_times(&classes, day)))
+            .collect();
+
+        Self {
+            credits: classes.iter().map(|x| x.credits).sum(),
+            idle: times.iter().flat_map(|(_, x)| gaps(x)).sum(),
+            days: days.len(),
+            bounds: times
+                .iter()
+                .map(|(day, x)| {
+                    let start = x.iter().map(|x| x.start).min().unwrap();
+                    let end = x.iter().map(|x| x.end).max().unwrap();
+
+                    (*day, TimeRange { start, end })
+                })
+                .collect(),
+            longest_stretch: times
+                .iter()
+                .flat_map(|(_, x)| stretches(x, BREAK_LENGTH))
+                .max()
+                .unwrap_or(0),
+            tight: transitions(schedule, passing)
+                .iter()
+                .filter(|x| x.tight())
+                .count(),
+        }
+    }
+}
+
+/// Print a row of metrics for every schedule
+pub fn print_table(schedules: &[(Vec<&Section>, Metrics)]) {
+    print!(
+        "{:>4} {:<8} {:>7} {:>4} {:>5} {:>7} {:>5}",
+        "#", "ID", "Credits", "Days", "Idle", "Longest", "Tight"
+    );
+    for day in Day::ALL {
+        print!(" {:>11}", format!("{day:?}"));
+    }
+    println!();
+
+    for (i, (data, metrics)) in schedules.iter().enumerate() {
+        print!(
+            "{:>4} {:<8} {:>7} {:>4} {:>5} {:>7} {:>5}",
+            i,
+            schedule_id(data),
+            metrics.credits.to_string(),
+            metrics.days,
+            Time::from_min(metrics.idle).to_string(),
+            Time::from_min(metrics.longest_stretch).to_string(),
+            metrics.tight,
+        );
+        for day in Day::ALL {
+            let bounds = metrics
+                .bounds
+                .iter()
+                .find(|(x, _)| *x == day)
+                .map(|(_, x)| x.to_string())
+                .unwrap_or_default();
+            print!(" {bounds:>11}");
+        }
+        println!();
+    }
+}
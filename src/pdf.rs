@@ -0,0 +1,129 @@
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+/// Write a PDF with one RGB image of `size` pixels filling each page
+pub fn write_pdf(
+    mut writer: impl Write,
+    size: (u32, u32),
+    pages: &[Vec<u8>],
+) -> std::io::Result<()> {
+    let (width, height) = size;
+
+    // Object 1 is the catalog, 2 is the page tree,
+    // then each page has a page, contents and image object
+    let page_id = |i: usize| 3 + i * 3;
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|i| format!("{} 0 R", page_id(i)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    ];
+
+    for (i, page) in pages.iter().enumerate() {
+        let id = page_id(i);
+
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {width} {height}] \
+                 /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                id + 2,
+                id + 1
+            )
+            .into_bytes(),
+        );
+
+        let contents = format!("q {width} 0 0 {height} 0 0 cm /Im0 Do Q");
+        objects.push(
+            format!(
+                "<< /Length {} >>\nstream\n{contents}\nendstream",
+                contents.len()
+            )
+            .into_bytes(),
+        );
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(page)?;
+        let data = encoder.finish()?;
+
+        let mut image = format!(
+            "<< /Type /XObject /Subtype /Image /Width {width} /Height {height} \
+             /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode /Length {} >>\nstream\n",
+            data.len()
+        )
+        .into_bytes();
+        image.extend(data);
+        image.extend(b"\nendstream");
+        objects.push(image);
+    }
+
+    let mut out = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend(format!("{} 0 obj\n", i + 1).into_bytes());
+        out.extend(object);
+        out.extend(b"\nendobj\n");
+    }
+
+    let xref = out.len();
+    out.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        out.extend(format!("{offset:010} 00000 n \n").into_bytes());
+    }
+    out.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        )
+        .into_bytes(),
+    );
+
+    writer.write_all(&out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xref_offsets() {
+        let size = (2, 2);
+        let pages = vec![vec![0; 12], vec![255; 12]];
+        let mut out = Vec::new();
+        write_pdf(&mut out, size, &pages).unwrap();
+
+        let tail = b"startxref\n";
+        let start = out.windows(tail.len()).rposition(|x| x == tail).unwrap() + tail.len();
+        let xref: usize = String::from_utf8_lossy(&out[start..])
+            .lines()
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(out[xref..].starts_with(b"xref\n"));
+
+        let table = String::from_utf8_lossy(&out[xref..]).into_owned();
+        let mut lines = table.lines().skip(1);
+        assert_eq!(lines.next(), Some("0 9"));
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+
+        // Catalog, page tree, then a page, contents and image for each page
+        for n in 1..=2 + pages.len() * 3 {
+            let entry = lines.next().unwrap();
+            assert!(entry.ends_with(" 00000 n "));
+
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(out[offset..].starts_with(format!("{n} 0 obj").as_bytes()));
+        }
+        assert_eq!(lines.next(), Some("trailer"));
+    }
+}
@@ -0,0 +1,590 @@
+use std::error::Error;
+use std::fs::File;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::id::schedule_id;
+use crate::pdf::write_pdf;
+use crate::{Campus, Day, DayTime, Metrics, Section, Time};
+
+const OUTPUT_DIR: &str = "output_images";
+
+/// File formats that each schedule can be drawn to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Png,
+    Svg,
+}
+
+/// How schedules are drawn
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub title_font: &'static str,
+    pub font: &'static str,
+    pub font_size: u32,
+    pub background: RGBColor,
+    /// Courses get a color from here based on their place in the course list
+    pub palette: &'static [RGBColor],
+    /// Color of blocked times
+    pub busy: RGBColor,
+    /// First and last hour shown, fit to the schedule if `None`
+    pub hours: Option<(u32, u32)>,
+    pub legend: bool,
+    /// Outline of sections that differ between schedules
+    pub highlight: RGBColor,
+}
+
+/// Width of the legend, in pixels
+const LEGEND_WIDTH: u32 = 170;
+/// Most schedules side by side in a comparison
+const COMPARE_COLUMNS: usize = 4;
+/// Height of the metrics under each schedule in a comparison, in pixels
+const FOOTER_HEIGHT: u32 = 44;
+/// Smallest font used to fit a label into a short block
+const MIN_FONT_SIZE: u32 = 9;
+/// Pixels between the lines of a block label
+const LINE_SPACING: u32 = 1;
+
+impl Theme {
+    /// The same course always gets the same color, and courses in one schedule
+    /// only share one when there are more courses than colors
+    fn color(&self, section: &Section) -> RGBColor {
+        if section.busy {
+            return self.busy;
+        }
+
+        self.palette[section.course % self.palette.len()]
+    }
+
+    /// First and last hour to show for `data`
+    fn hours(&self, data: &[&Section]) -> (u32, u32) {
+        if let Some(hours) = self.hours {
+            return hours;
+        }
+
+        let meetings = data.iter().flat_map(|x| x.meetings.iter());
+        let start = meetings.clone().map(|x| x.time.start.hour).min();
+        let end = meetings
+            .map(|x| x.time.end.hour + (x.time.end.minute > 0) as u32)
+            .max();
+
+        match (start, end) {
+            (Some(start), Some(end)) => (start, end.max(start + 1)),
+            _ => (8, 8 + 11),
+        }
+    }
+}
+
+/// Draw the week view of one schedule onto `root`
+pub fn draw_schedule<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[&Section],
+    metrics: &Metrics,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    draw_schedule_marked(root, data, metrics, &[], theme)
+}
+
+/// Draw the week view of one schedule, outlining the sections in `marked`
+fn draw_schedule_marked<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[&Section],
+    metrics: &Metrics,
+    marked: &[&Section],
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let credits = metrics.credits;
+    root.fill(&theme.background)?;
+
+    let (chart_area, legend_area) = if theme.legend {
+        root.split_horizontally(root.dim_in_pixel().0.saturating_sub(LEGEND_WIDTH))
+    } else {
+        root.split_horizontally(root.dim_in_pixel().0)
+    };
+
+    let w_count = 5;
+    let (h_start, h_end) = theme.hours(data);
+    let h_count = h_end - h_start;
+
+    // The following code will create a chart context
+    let mut chart = ChartBuilder::on(&chart_area)
+        .caption(
+            format!("Schedule ({credits} credits)"),
+            (theme.title_font, 20).into_font(),
+        )
+        .x_label_area_size(40)
+        .y_label_area_size(40)
+        .build_cartesian_2d(
+            0.5f32..(w_count as f32 + 0.5),
+            (h_end as f32)..h_start as f32,
+        )?;
+
+    chart
+        .configure_mesh()
+        .y_labels(h_count as usize + 1)
+        .y_label_formatter(&|&x| hour_label(x as u32))
+        .x_label_formatter(&|&x| {
+            if x.fract() != 0.0 {
+                return "".to_string();
+            }
+
+            match x as i32 {
+                1 => "Mon",
+                2 => "Tue",
+                3 => "Wed",
+                4 => "Thu",
+                5 => "Fri",
+                _ => "",
+            }
+            .to_string()
+        })
+        .label_style((theme.font, 12))
+        .light_line_style(TRANSPARENT)
+        .disable_x_mesh()
+        .draw()?;
+
+    let (w, h) = chart.plotting_area().dim_in_pixel();
+    let size = |len| {
+        let width = w as i32 / (w_count);
+        let height = h as f32 / (h_count as f32) * len;
+        [(-width / 2, 0), (width / 2, height as i32)]
+    };
+
+    let position = |meeting: &DayTime| {
+        let pos_x = match meeting.meet_day {
+            Day::Mon => 1.0,
+            Day::Tue => 2.0,
+            Day::Wed => 3.0,
+            Day::Thu => 4.0,
+            Day::Fri => 5.0,
+        };
+
+        let pos_y = meeting.time.start.hour as f32 + meeting.time.start.minute as f32 / 60.;
+
+        (pos_x, pos_y)
+    };
+
+    chart.draw_series(data.iter().flat_map(|x| {
+        x.meetings.iter().map(|meeting| {
+            let size = size(meeting.time.len() as f32 / 60.);
+
+            EmptyElement::at(position(meeting))
+                + Rectangle::new(size, theme.color(x).filled())
+                + if marked.contains(x) {
+                    Rectangle::new(size, theme.highlight.stroke_width(4))
+                } else {
+                    Rectangle::new(size, theme.background.stroke_width(2))
+                }
+        })
+    }))?;
+
+    let text_width = |text: &str, font_size: u32| {
+        root.estimate_text_size(text, &(theme.font, font_size).into_font().into())
+            .map(|(w, _)| w)
+            .unwrap_or(text.len() as u32 * font_size / 2)
+    };
+
+    chart.draw_series(data.iter().flat_map(|x| {
+        x.meetings.iter().flat_map(move |meeting| {
+            let [(left, top), (right, bottom)] = size(meeting.time.len() as f32 / 60.);
+            let lines = [
+                format!("{} {}", x.class, x.section),
+                meeting.time.to_string(),
+                x.professor.split(' ').next().unwrap().to_owned(),
+            ];
+
+            let (font_size, lines) = layout_label(
+                &lines,
+                right - left - 4,
+                bottom - top - 4,
+                theme,
+                text_width,
+            );
+
+            lines.into_iter().enumerate().map(move |(i, line)| {
+                EmptyElement::at(position(meeting))
+                    + Text::new(
+                        line,
+                        (
+                            left + 2,
+                            top + 2 + i as i32 * (font_size + LINE_SPACING) as i32,
+                        ),
+                        (theme.font, font_size).into_font(),
+                    )
+            })
+        })
+    }))?;
+
+    if theme.legend {
+        draw_legend(&legend_area, data, theme)?;
+    }
+
+    Ok(())
+}
+
+/// Label for a whole hour on the 12-hour clock
+fn hour_label(hour: u32) -> String {
+    let suffix = if hour % 24 < 12 { "AM" } else { "PM" };
+
+    match hour % 12 {
+        0 => format!("12 {suffix}"),
+        x => format!("{x} {suffix}"),
+    }
+}
+
+/// Lines of a block label that fit in `width` x `height` pixels, shrinking
+/// the font down to `MIN_FONT_SIZE` before dropping the last lines and
+/// cutting the rest short
+fn layout_label(
+    lines: &[String],
+    width: i32,
+    height: i32,
+    theme: &Theme,
+    text_width: impl Fn(&str, u32) -> u32,
+) -> (u32, Vec<String>) {
+    let fits = |font_size: u32| (height / (font_size + LINE_SPACING) as i32).max(0) as usize;
+
+    let font_size = (MIN_FONT_SIZE..=theme.font_size.max(MIN_FONT_SIZE))
+        .rev()
+        .find(|&x| {
+            fits(x) >= lines.len() && lines.iter().all(|line| text_width(line, x) as i32 <= width)
+        })
+        .unwrap_or(MIN_FONT_SIZE);
+
+    let lines = lines
+        .iter()
+        .take(fits(font_size))
+        .map(|line| {
+            if text_width(line, font_size) as i32 <= width {
+                return line.clone();
+            }
+
+            let mut line: String = line.clone();
+            while !line.is_empty() && text_width(&format!("{line}…"), font_size) as i32 > width {
+                line.pop();
+            }
+
+            if line.is_empty() {
+                line
+            } else {
+                line + "…"
+            }
+        })
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    (font_size, lines)
+}
+
+/// List the color, name and campus of each section
+fn draw_legend<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    data: &[&Section],
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let line_height = theme.font_size as i32 + 6;
+
+    for (i, x) in data.iter().enumerate() {
+        let y = 40 + i as i32 * line_height;
+
+        let campus = if x.meetings.iter().any(|m| m.campus == Some(Campus::North)) {
+            " (North)"
+        } else {
+            ""
+        };
+
+        root.draw(&Rectangle::new(
+            [
+                (4, y),
+                (4 + theme.font_size as i32, y + theme.font_size as i32),
+            ],
+            theme.color(x).filled(),
+        ))?;
+        root.draw(&Text::new(
+            format!("{} {}{campus}", x.class, x.section),
+            (8 + theme.font_size as i32, y),
+            (theme.font, theme.font_size as i32).into_font(),
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Draw every schedule to its own file
+pub fn draw_classes(
+    schedules: &[(Vec<&Section>, Metrics)],
+    format: Format,
+    size: (u32, u32),
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    _ = std::fs::create_dir(OUTPUT_DIR);
+
+    for (data, metrics) in schedules {
+        let id = schedule_id(data);
+
+        match format {
+            Format::Png => {
+                let name = format!("{OUTPUT_DIR}/{id}.png");
+                let root = BitMapBackend::new(&name, size).into_drawing_area();
+                draw_schedule(&root, data, metrics, theme)?;
+                root.present()?;
+            }
+            Format::Svg => {
+                let name = format!("{OUTPUT_DIR}/{id}.svg");
+                let root = SVGBackend::new(&name, size).into_drawing_area();
+                draw_schedule(&root, data, metrics, theme)?;
+                root.present()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// SVG markup of one schedule
+pub fn schedule_svg(
+    data: &[&Section],
+    metrics: &Metrics,
+    size: (u32, u32),
+    theme: &Theme,
+) -> Result<String, Box<dyn Error>> {
+    let mut svg = String::new();
+    {
+        let root = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+        draw_schedule(&root, data, metrics, theme)?;
+        root.present()?;
+    }
+
+    Ok(svg)
+}
+
+/// Draw the first `count` schedules in a grid on one image
+pub fn draw_sheet(
+    schedules: &[(Vec<&Section>, Metrics)],
+    format: Format,
+    size: (u32, u32),
+    count: usize,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    _ = std::fs::create_dir(OUTPUT_DIR);
+
+    let schedules = &schedules[..count.min(schedules.len())];
+    let cols = (schedules.len() as f64).sqrt().ceil().max(1.) as usize;
+    let rows = schedules.len().div_ceil(cols).max(1);
+    let sheet_size = (size.0 * cols as u32, size.1 * rows as u32);
+
+    match format {
+        Format::Png => {
+            let name = format!("{OUTPUT_DIR}/sheet.png");
+            let root = BitMapBackend::new(&name, sheet_size).into_drawing_area();
+            draw_sheet_on(&root, schedules, rows, cols, theme)?;
+            root.present()?;
+        }
+        Format::Svg => {
+            let name = format!("{OUTPUT_DIR}/sheet.svg");
+            let root = SVGBackend::new(&name, sheet_size).into_drawing_area();
+            draw_sheet_on(&root, schedules, rows, cols, theme)?;
+            root.present()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_sheet_on<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    schedules: &[(Vec<&Section>, Metrics)],
+    rows: usize,
+    cols: usize,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&theme.background)?;
+
+    for (area, (data, metrics)) in root.split_evenly((rows, cols)).iter().zip(schedules) {
+        draw_schedule(area, data, metrics, theme)?;
+    }
+
+    Ok(())
+}
+
+/// Draw the chosen schedules next to each other with their metrics under each,
+/// outlining the sections in `marked`, to `name` in the output folder
+pub fn draw_comparison(
+    name: &str,
+    schedules: &[(usize, &(Vec<&Section>, Metrics))],
+    marked: &[&Section],
+    format: Format,
+    size: (u32, u32),
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    _ = std::fs::create_dir(OUTPUT_DIR);
+
+    let cols = schedules.len().clamp(1, COMPARE_COLUMNS);
+    let rows = schedules.len().div_ceil(cols).max(1);
+    let sheet_size = (size.0 * cols as u32, (size.1 + FOOTER_HEIGHT) * rows as u32);
+
+    match format {
+        Format::Png => {
+            let name = format!("{OUTPUT_DIR}/{name}.png");
+            let root = BitMapBackend::new(&name, sheet_size).into_drawing_area();
+            draw_comparison_on(&root, schedules, marked, rows, cols, theme)?;
+            root.present()?;
+        }
+        Format::Svg => {
+            let name = format!("{OUTPUT_DIR}/{name}.svg");
+            let root = SVGBackend::new(&name, sheet_size).into_drawing_area();
+            draw_comparison_on(&root, schedules, marked, rows, cols, theme)?;
+            root.present()?;
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_comparison_on<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    schedules: &[(usize, &(Vec<&Section>, Metrics))],
+    marked: &[&Section],
+    rows: usize,
+    cols: usize,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&theme.background)?;
+
+    for (area, (i, (data, metrics))) in root.split_evenly((rows, cols)).iter().zip(schedules) {
+        let height = area.dim_in_pixel().1;
+        let (chart, footer) = area.split_vertically(height.saturating_sub(FOOTER_HEIGHT));
+        draw_schedule_marked(&chart, data, metrics, marked, theme)?;
+
+        let lines = [
+            format!(
+                "#{i} {}: {} credits, {} days",
+                schedule_id(data),
+                metrics.credits,
+                metrics.days
+            ),
+            format!(
+                "Idle {}, longest stretch {}, {} tight",
+                Time::from_min(metrics.idle),
+                Time::from_min(metrics.longest_stretch),
+                metrics.tight
+            ),
+        ];
+        for (j, line) in lines.into_iter().enumerate() {
+            footer.draw(&Text::new(
+                line,
+                (40, 4 + j as i32 * (theme.font_size + 4) as i32),
+                (theme.font, theme.font_size).into_font(),
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Draw the first `count` schedules to a PDF, one schedule per page
+pub fn draw_pdf(
+    schedules: &[(Vec<&Section>, Metrics)],
+    size: (u32, u32),
+    count: usize,
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    _ = std::fs::create_dir(OUTPUT_DIR);
+
+    let mut pages = Vec::new();
+    for (data, metrics) in schedules.iter().take(count) {
+        let mut buffer = vec![0; size.0 as usize * size.1 as usize * 3];
+        {
+            let root = BitMapBackend::with_buffer(&mut buffer, size).into_drawing_area();
+            draw_schedule(&root, data, metrics, theme)?;
+            root.present()?;
+        }
+        pages.push(buffer);
+    }
+
+    let file = File::create(format!("{OUTPUT_DIR}/schedules.pdf"))?;
+    write_pdf(file, size, &pages)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THEME: Theme = Theme {
+        title_font: "sans-serif",
+        font: "sans-serif",
+        font_size: 14,
+        background: WHITE,
+        palette: &[],
+        busy: BLACK,
+        hours: None,
+        legend: false,
+        highlight: BLACK,
+    };
+
+    /// Every character is 0.6 of the font size wide
+    fn text_width(text: &str, font_size: u32) -> u32 {
+        text.chars().count() as u32 * font_size * 6 / 10
+    }
+
+    fn label() -> Vec<String> {
+        ["CSC 246 001", "8:30-9:45", "Jane Doe"]
+            .map(String::from)
+            .to_vec()
+    }
+
+    #[test]
+    fn hour_labels() {
+        assert_eq!(hour_label(0), "12 AM");
+        assert_eq!(hour_label(11), "11 AM");
+        assert_eq!(hour_label(12), "12 PM");
+        assert_eq!(hour_label(13), "1 PM");
+        assert_eq!(hour_label(23), "11 PM");
+    }
+
+    #[test]
+    fn label_fits_tall_block() {
+        assert_eq!(
+            layout_label(&label(), 500, 100, &THEME, text_width),
+            (14, label())
+        );
+    }
+
+    #[test]
+    fn label_shrinks_font() {
+        assert_eq!(
+            layout_label(&label(), 500, 33, &THEME, text_width),
+            (10, label())
+        );
+    }
+
+    #[test]
+    fn label_truncated_in_short_block() {
+        let (width, height) = (50, 25);
+        let (font_size, lines) = layout_label(&label(), width, height, &THEME, text_width);
+
+        assert_eq!(font_size, MIN_FONT_SIZE);
+        assert_eq!(lines, ["CSC 246 …", "8:30-9:45"]);
+        for line in &lines {
+            assert!(text_width(line, font_size) as i32 <= width);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::Write;
+
+use crate::id::schedule_id;
+use crate::render::{schedule_svg, Theme};
+use crate::requisite::requisites_met;
+use crate::{Metrics, Rating, Section, Time};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 1em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; vertical-align: top; }
+th { background: #eee; cursor: pointer; user-select: none; }
+th.asc::after { content: ' \\25B2'; }
+th.desc::after { content: ' \\25BC'; }
+#filters { margin-bottom: 1em; }
+#filters label { margin-right: 1em; }
+input[type=number] { width: 5em; }
+";
+
+const SCRIPT: &str = "
+const table = document.getElementById('schedules');
+const body = table.tBodies[0];
+
+table.querySelectorAll('th').forEach((th, column) => {
+    th.addEventListener('click', () => {
+        const asc = !th.classList.contains('asc');
+        table.querySelectorAll('th').forEach(x => x.classList.remove('asc', 'desc'));
+        th.classList.add(asc ? 'asc' : 'desc');
+
+        const value = row => row.cells[column].dataset.value ?? row.cells[column].textContent;
+        const rows = Array.from(body.rows).sort((a, b) => {
+            const x = value(a), y = value(b);
+            const order = isNaN(x) || isNaN(y) ? x.localeCompare(y) : x - y;
+            return asc ? order : -order;
+        });
+        body.append(...rows);
+    });
+});
+
+function filter() {
+    const get = id => document.getElementById(id).value;
+    const search = get('search').toLowerCase();
+
+    let shown = 0;
+    for (const row of body.rows) {
+        const d = row.dataset;
+        const show = (get('credits') === '' || +d.credits >= +get('credits'))
+            && (get('days') === '' || +d.days <= +get('days'))
+            && (get('idle') === '' || +d.idle <= +get('idle') * 60)
+            && (get('tight') === '' || +d.tight <= +get('tight'))
+            && d.text.includes(search);
+        row.hidden = !show;
+        shown += show;
+    }
+    document.getElementById('shown').textContent = shown;
+}
+
+document.querySelectorAll('#filters input').forEach(x => x.addEventListener('input', filter));
+";
+
+/// Escape text for HTML content and attribute values
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Share of A grades averaged over the professors that have a rating, from 0 to 1
+fn rating(schedule: &[&Section], ratings: &HashMap<String, Rating>) -> Option<f64> {
+    let rated: Vec<f64> = schedule
+        .iter()
+        .filter_map(|x| ratings.get(&Rating::key(&x.professor)))
+        .map(|x| x.percent_a)
+        .collect();
+
+    (!rated.is_empty()).then(|| rated.iter().sum::<f64>() / rated.len() as f64)
+}
+
+/// Write a self-contained page with a sortable, filterable table of every schedule
+pub fn write_report(
+    mut writer: impl Write,
+    schedules: &[(Vec<&Section>, Metrics)],
+    ratings: &HashMap<String, Rating>,
+    completed: &[&str],
+    size: (u32, u32),
+    theme: &Theme,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        writer,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Schedules</title>\n<style>{STYLE}</style>\n</head>\n<body>"
+    )?;
+    writeln!(
+        writer,
+        "<h1>Schedules</h1>\n<div id=\"filters\">\
+        <label>Min credits <input type=\"number\" id=\"credits\" min=\"0\"></label>\
+        <label>Max days <input type=\"number\" id=\"days\" min=\"0\" max=\"5\"></label>\
+        <label>Max idle hours <input type=\"number\" id=\"idle\" min=\"0\" step=\"0.5\"></label>\
+        <label>Max tight <input type=\"number\" id=\"tight\" min=\"0\"></label>\
+        <label>Search <input type=\"search\" id=\"search\" placeholder=\"class or professor\"></label>\
+        <span id=\"shown\">{}</span> of {} shown</div>",
+        schedules.len(),
+        schedules.len()
+    )?;
+    writeln!(
+        writer,
+        "<table id=\"schedules\">\n<thead><tr><th>#</th><th>ID</th><th>Credits</th><th>Days</th><th>Idle</th>\
+        <th>Longest</th><th>Tight</th><th>Sections</th><th>Professors</th><th>Rating</th><th>Week</th></tr></thead>\n<tbody>"
+    )?;
+
+    for (i, (data, metrics)) in schedules.iter().enumerate() {
+        let classes: Vec<&Section> = data.iter().filter(|x| !x.busy).copied().collect();
+        let sections: Vec<String> = classes
+            .iter()
+            .map(|x| {
+                let unmet = if requisites_met(x, completed) {
+                    ""
+                } else {
+                    " (requisites not met)"
+                };
+                escape(&format!("{} {}{unmet}", x.class, x.section))
+            })
+            .collect();
+        let professors: Vec<String> = classes.iter().map(|x| escape(&x.professor)).collect();
+        let text = format!("{} {}", sections.join(" "), professors.join(" ")).to_lowercase();
+        let rating = rating(&classes, ratings);
+
+        let id = schedule_id(data);
+
+        writeln!(
+            writer,
+            "<tr id=\"{id}\" data-credits=\"{}\" data-days=\"{}\" data-idle=\"{}\" data-tight=\"{}\" data-text=\"{text}\">",
+            metrics.credits.max, metrics.days, metrics.idle, metrics.tight
+        )?;
+        writeln!(
+            writer,
+            "<td>{i}</td><td><a href=\"#{id}\">{id}</a></td><td data-value=\"{}\">{}</td><td>{}</td>\
+            <td data-value=\"{}\">{}</td><td data-value=\"{}\">{}</td><td>{}</td>",
+            metrics.credits.min,
+            metrics.credits,
+            metrics.days,
+            metrics.idle,
+            Time::from_min(metrics.idle),
+            metrics.longest_stretch,
+            Time::from_min(metrics.longest_stretch),
+            metrics.tight
+        )?;
+        writeln!(
+            writer,
+            "<td>{}</td><td>{}</td><td data-value=\"{}\">{}</td>",
+            sections.join("<br>"),
+            professors.join("<br>"),
+            rating.unwrap_or(-1.),
+            rating
+                .map(|x| format!("{:.0}% A", x * 100.))
+                .unwrap_or_default()
+        )?;
+        writeln!(
+            writer,
+            "<td><details><summary>Show</summary>{}</details></td>\n</tr>",
+            schedule_svg(data, metrics, size, theme)?
+        )?;
+    }
+
+    writeln!(
+        writer,
+        "</tbody>\n</table>\n<script>{SCRIPT}</script>\n</body>\n</html>"
+    )?;
+
+    Ok(())
+}
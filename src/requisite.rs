@@ -0,0 +1,481 @@
+use std::fmt::Display;
+
+use crate::crosslist::parse_course_codes;
+use crate::Section;
+
+/// Courses that have to be taken, like `(MA 241 or MA 231) and PY 205`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Requisite {
+    Course(String),
+    /// Something other than a course like `Junior standing`, which is assumed met
+    Other(String),
+    All(Vec<Requisite>),
+    Any(Vec<Requisite>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Course(String),
+    Other(String),
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+/// Courses, operators and parentheses in `text`. Words next to a course like
+/// `with a grade of C` only qualify it and are dropped, other words are kept
+/// as an alternative to courses like `Junior standing`. Operators that don't
+/// join two terms are dropped.
+fn tokenize(text: &str) -> Vec<Token> {
+    // `C or better` is a grade, not an alternative
+    let mut text = text.replace('(', " ( ").replace(')', " ) ");
+    for phrase in ["or better", "or higher", "or above"] {
+        while let Some(i) = text.to_ascii_lowercase().find(phrase) {
+            text.replace_range(i..i + phrase.len(), "");
+        }
+    }
+
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || ",;:.".contains(c))
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let token = match words[i] {
+            "(" => Token::Open,
+            ")" => Token::Close,
+            x if x.eq_ignore_ascii_case("and") => Token::And,
+            x if x.eq_ignore_ascii_case("or") => Token::Or,
+            // A subject followed by a number, like `CSC 216`
+            x => match words
+                .get(i + 1)
+                .map(|y| parse_course_codes(&format!("{x} {y}")))
+            {
+                Some(codes) if codes.len() == 1 => {
+                    i += 1;
+                    Token::Course(codes.into_iter().next().unwrap())
+                }
+                _ => Token::Other(x.to_owned()),
+            },
+        };
+        i += 1;
+
+        match (token, tokens.last_mut()) {
+            (
+                op @ (Token::And | Token::Or),
+                Some(Token::Course(_) | Token::Other(_) | Token::Close),
+            ) => tokens.push(op),
+            (op @ (Token::And | Token::Or), Some(last @ (Token::And | Token::Or))) => *last = op,
+            (Token::And | Token::Or, _) => {}
+            (Token::Other(_), Some(Token::Course(_) | Token::Close)) => {}
+            (Token::Other(word), Some(Token::Other(last))) => {
+                last.push(' ');
+                last.push_str(&word);
+            }
+            (course @ Token::Course(_), Some(last @ Token::Other(_))) => *last = course,
+            (token, _) => tokens.push(token),
+        }
+    }
+
+    if matches!(tokens.last(), Some(Token::And | Token::Or)) {
+        tokens.pop();
+    }
+
+    tokens
+}
+
+/// `or` of `and`s of courses or parenthesized groups
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn any(&mut self) -> Option<Requisite> {
+        let mut terms = Vec::new();
+
+        loop {
+            terms.extend(self.all());
+            match self.peek() {
+                Some(Token::Or) => self.pos += 1,
+                _ => break,
+            }
+        }
+
+        Requisite::flatten(terms, false)
+    }
+
+    fn all(&mut self) -> Option<Requisite> {
+        let mut terms = Vec::new();
+
+        loop {
+            terms.extend(self.term());
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                _ => break,
+            }
+        }
+
+        Requisite::flatten(terms, true)
+    }
+
+    fn term(&mut self) -> Option<Requisite> {
+        match self.peek()?.clone() {
+            Token::Course(code) => {
+                self.pos += 1;
+                Some(Requisite::Course(code))
+            }
+            Token::Other(text) => {
+                self.pos += 1;
+                Some(Requisite::Other(text))
+            }
+            Token::Open => {
+                self.pos += 1;
+                let res = self.any();
+                if self.peek() == Some(&Token::Close) {
+                    self.pos += 1;
+                }
+                res
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Requisite {
+    /// `All` or `Any` of `terms`, merging nested groups of the same kind
+    fn flatten(terms: Vec<Requisite>, all: bool) -> Option<Self> {
+        let mut flat = Vec::new();
+        for term in terms {
+            match term {
+                Requisite::All(x) if all => flat.extend(x),
+                Requisite::Any(x) if !all => flat.extend(x),
+                x => flat.push(x),
+            }
+        }
+
+        match flat.len() {
+            0 => None,
+            1 => flat.pop(),
+            _ if all => Some(Requisite::All(flat)),
+            _ => Some(Requisite::Any(flat)),
+        }
+    }
+
+    /// Parse the courses in a description like `CSC 216 and (CSC 226 or MA 225)`
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(text),
+            pos: 0,
+        };
+
+        let mut terms = Vec::new();
+        while parser.pos < parser.tokens.len() {
+            match parser.any() {
+                Some(x) => terms.push(x),
+                None => parser.pos += 1,
+            }
+        }
+
+        Self::flatten(terms, true)
+    }
+
+    pub fn met(&self, completed: &[&str]) -> bool {
+        match self {
+            Requisite::Course(code) => completed.contains(&code.as_str()),
+            Requisite::Other(_) => true,
+            Requisite::All(x) => x.iter().all(|x| x.met(completed)),
+            Requisite::Any(x) => x.iter().any(|x| x.met(completed)),
+        }
+    }
+}
+
+impl Display for Requisite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |f: &mut std::fmt::Formatter<'_>, terms: &[Requisite], sep: &str| {
+            for (i, term) in terms.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(sep)?;
+                }
+                match term {
+                    Requisite::Course(_) | Requisite::Other(_) => term.fmt(f)?,
+                    _ => f.write_fmt(format_args!("({term})"))?,
+                }
+            }
+            Ok(())
+        };
+
+        match self {
+            Requisite::Course(code) | Requisite::Other(code) => f.write_str(code),
+            Requisite::All(x) => join(f, x, " and "),
+            Requisite::Any(x) => join(f, x, " or "),
+        }
+    }
+}
+
+/// Requisites under the `Prerequisite` labels of a description, or the
+/// `Corequisite` ones when `co` is set. Text before any label counts as
+/// prerequisites.
+fn labeled(descr: &str, co: bool) -> Option<Requisite> {
+    // Spell out `Co-requisite` and `Pre req` as one word, keeping the length
+    // so positions still line up with `descr`
+    let lower = descr
+        .to_ascii_lowercase()
+        .replace("co-req", " coreq")
+        .replace("co req", " coreq")
+        .replace("pre-req", " prereq")
+        .replace("pre req", " prereq");
+
+    let mut labels: Vec<(usize, bool)> = lower
+        .match_indices("prereq")
+        .map(|(i, _)| (i, false))
+        .chain(lower.match_indices("coreq").map(|(i, _)| (i, true)))
+        .collect();
+    labels.sort();
+
+    let mut parts = vec![(0, labels.first().map_or(descr.len(), |x| x.0), false)];
+    for (i, &(pos, kind)) in labels.iter().enumerate() {
+        // Skip the label itself, like `Corequisites:`
+        let start = lower[pos..]
+            .find(|c: char| c.is_whitespace() || c == ':')
+            .map_or(descr.len(), |x| pos + x);
+        let end = labels.get(i + 1).map_or(descr.len(), |x| x.0);
+        parts.push((start.min(end), end, kind));
+    }
+
+    let terms = parts
+        .into_iter()
+        .filter(|&(_, _, kind)| kind == co)
+        .filter_map(|(start, end, _)| Requisite::parse(&descr[start..end]))
+        .collect();
+
+    Requisite::flatten(terms, true)
+}
+
+/// Prerequisites in a description like `Prerequisite: MA 241. Corequisite: PY 209`
+pub fn prerequisites(descr: &str) -> Option<Requisite> {
+    labeled(descr, false)
+}
+
+/// Corequisites in a description like `Prerequisite: MA 241. Corequisite: PY 209`
+pub fn corequisites(descr: &str) -> Option<Requisite> {
+    labeled(descr, true)
+}
+
+/// Corequisites of a section from `rules` and its requisite description
+fn required_corequisites(section: &Section, rules: &[(&str, &str)]) -> Vec<Requisite> {
+    rules
+        .iter()
+        .filter(|(class, _)| *class == section.class)
+        .map(|(_, other)| Requisite::Course(other.to_string()))
+        .chain(corequisites(&section.requisites))
+        .collect()
+}
+
+/// Corequisites of each class in the schedule that are neither in it nor completed.
+/// `rules` are pairs of a class and the class it has to be taken with, on top
+/// of the ones in the requisite descriptions.
+pub fn missing_corequisites(
+    schedule: &[&Section],
+    rules: &[(&str, &str)],
+    completed: &[&str],
+) -> Vec<(String, Requisite)> {
+    let mut taken: Vec<&str> = completed.to_vec();
+    for x in schedule.iter().filter(|x| !x.busy) {
+        taken.push(&x.class);
+        taken.extend(x.cross_listed.iter().map(String::as_str));
+    }
+
+    let mut missing = Vec::new();
+    for x in schedule.iter().filter(|x| !x.busy) {
+        for requisite in required_corequisites(x, rules) {
+            if !requisite.met(&taken) && !missing.contains(&(x.class.clone(), requisite.clone())) {
+                missing.push((x.class.clone(), requisite));
+            }
+        }
+    }
+
+    missing
+}
+
+/// Corequisites of any loaded section that no loaded course or completed course
+/// can satisfy, so every schedule with that section misses them
+pub fn unavailable_corequisites(
+    classes: &[Vec<Section>],
+    rules: &[(&str, &str)],
+    completed: &[&str],
+) -> Vec<(String, Requisite)> {
+    let mut available: Vec<&str> = completed.to_vec();
+    for x in classes.iter().flatten() {
+        available.push(&x.class);
+        available.extend(x.cross_listed.iter().map(String::as_str));
+    }
+
+    let mut missing = Vec::new();
+    for x in classes.iter().flatten() {
+        for requisite in required_corequisites(x, rules) {
+            if !requisite.met(&available)
+                && !missing.contains(&(x.class.clone(), requisite.clone()))
+            {
+                missing.push((x.class.clone(), requisite));
+            }
+        }
+    }
+
+    missing
+}
+
+/// Whether the registration system says the requisites are met, and when
+/// `completed` isn't empty, whether the prerequisites are among those courses
+pub fn requisites_met(section: &Section, completed: &[&str]) -> bool {
+    section.requisite_met != Some(false)
+        && (completed.is_empty()
+            || prerequisites(&section.requisites).is_none_or(|x| x.met(completed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Credits;
+
+    fn course(code: &str) -> Requisite {
+        Requisite::Course(code.to_owned())
+    }
+
+    fn section(class: &str, requisites: &str) -> Section {
+        Section {
+            class: class.to_owned(),
+            section: "001".to_owned(),
+            facility: String::new(),
+            meetings: Vec::new(),
+            professor: String::new(),
+            credits: Credits::default(),
+            class_nbr: String::new(),
+            busy: false,
+            dates: String::new(),
+            cart: Vec::new(),
+            cross_listed: Vec::new(),
+            aliases: Vec::new(),
+            course: 0,
+            requisite_met: None,
+            requisites: requisites.to_owned(),
+        }
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            Requisite::parse("CSC 216 and CSC 226 or MA 241"),
+            Some(Requisite::Any(vec![
+                Requisite::All(vec![course("CSC 216"), course("CSC 226")]),
+                course("MA 241"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn parentheses() {
+        let requisite = Requisite::parse("(MA 241 or MA 231) and PY 205").unwrap();
+
+        assert_eq!(
+            requisite,
+            Requisite::All(vec![
+                Requisite::Any(vec![course("MA 241"), course("MA 231")]),
+                course("PY 205"),
+            ])
+        );
+        assert_eq!(requisite.to_string(), "(MA 241 or MA 231) and PY 205");
+        assert!(requisite.met(&["MA 231", "PY 205"]));
+        assert!(!requisite.met(&["MA 241", "MA 231"]));
+    }
+
+    #[test]
+    fn grade_requirement() {
+        assert_eq!(
+            prerequisites("Prerequisite: CSC 216 with a grade of C or better and CSC 226"),
+            Some(Requisite::All(vec![course("CSC 216"), course("CSC 226")]))
+        );
+    }
+
+    #[test]
+    fn comma_list() {
+        assert_eq!(
+            Requisite::parse("CSC 216, CSC 226, and MA 241"),
+            Some(Requisite::All(vec![
+                course("CSC 216"),
+                course("CSC 226"),
+                course("MA 241"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn corequisite_before_prerequisite() {
+        let descr = "Corequisite: PY 208. Prerequisite: MA 241";
+
+        assert_eq!(prerequisites(descr), Some(course("MA 241")));
+        assert_eq!(corequisites(descr), Some(course("PY 208")));
+    }
+
+    #[test]
+    fn hyphenated_labels() {
+        let descr = "Prereq: CSC 216; Co-requisite: CSC 217";
+
+        assert_eq!(prerequisites(descr), Some(course("CSC 216")));
+        assert_eq!(corequisites(descr), Some(course("CSC 217")));
+        assert_eq!(
+            corequisites("Pre-requisite: MA 241. Co requisite: PY 209"),
+            Some(course("PY 209"))
+        );
+    }
+
+    #[test]
+    fn non_course_alternative_is_met() {
+        let requisite = prerequisites("Prerequisite: Junior standing or CSC 216").unwrap();
+
+        assert!(requisite.met(&[]));
+        assert_eq!(
+            prerequisites("Junior standing").map(|x| x.met(&[])),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn requisites_met_against_completed() {
+        let mut x = section("CSC 326", "Prerequisite: CSC 216 and CSC 226");
+
+        // Nothing to check against without completed courses
+        assert!(requisites_met(&x, &[]));
+        assert!(!requisites_met(&x, &["CSC 216"]));
+        assert!(requisites_met(&x, &["CSC 216", "CSC 226"]));
+
+        x.requisite_met = Some(false);
+        assert!(!requisites_met(&x, &["CSC 216", "CSC 226"]));
+    }
+
+    #[test]
+    fn corequisites_in_schedule() {
+        let lecture = section("PY 208", "Prerequisite: MA 241; Corequisite: PY 209");
+        let lab = section("PY 209", "");
+
+        assert_eq!(
+            missing_corequisites(&[&lecture], &[], &[]),
+            vec![("PY 208".to_owned(), course("PY 209"))]
+        );
+        assert!(missing_corequisites(&[&lecture, &lab], &[], &[]).is_empty());
+        assert!(missing_corequisites(&[&lecture], &[], &["PY 209"]).is_empty());
+
+        // Rules from the config apply on top of the description
+        assert_eq!(
+            missing_corequisites(&[&lab], &[("PY 209", "PY 208")], &[]),
+            vec![("PY 209".to_owned(), course("PY 208"))]
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt::Display;
+
+use crate::{Day, DayTime, Section};
+
+/// Transitions with less spare time than this are tight, in minutes
+const TIGHT_SLACK: i32 = 10;
+
+/// Whether a gap of exactly the passing time is too short
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// The gap must be longer than the passing time, so touching classes conflict
+    Inclusive,
+    /// The gap may be equal to the passing time, so touching classes are fine without one
+    Exclusive,
+}
+
+/// Minutes needed between the end of one meeting and the start of the next
+#[derive(Debug, Clone, Copy)]
+pub struct PassingTime {
+    pub same_building: u32,
+    pub same_campus: u32,
+    pub different_campus: u32,
+    pub boundary: Boundary,
+}
+
+impl PassingTime {
+    /// Passing time needed between `this` and `other`
+    pub fn required(&self, this: &DayTime, other: &DayTime) -> u32 {
+        match (this.campus, other.campus) {
+            (Some(a), Some(b)) if a != b => self.different_campus,
+            (Some(_), Some(_)) if this.building.is_some() && this.building == other.building => {
+                self.same_building
+            }
+            (Some(_), Some(_)) => self.same_campus,
+            // Without a location only overlap matters
+            _ => 0,
+        }
+    }
+
+    /// Whether a gap of `gap` minutes is enough when `required` minutes are needed
+    pub fn allows(&self, gap: i32, required: u32) -> bool {
+        match self.boundary {
+            Boundary::Inclusive => gap > required as i32,
+            Boundary::Exclusive => gap >= required as i32,
+        }
+    }
+
+    /// Whether there is enough time to get between `this` and `other`
+    pub fn enough(&self, this: &DayTime, other: &DayTime) -> bool {
+        this.meet_day != other.meet_day
+            || self.allows(this.time.gap(&other.time), self.required(this, other))
+    }
+}
+
+/// Going from one meeting to the next one on the same day
+#[derive(Debug, Clone)]
+pub struct Transition<'a> {
+    pub from: (&'a Section, &'a DayTime),
+    pub to: (&'a Section, &'a DayTime),
+    /// Minutes between the meetings, negative if they overlap
+    pub gap: i32,
+    /// Passing time needed between the meetings
+    pub required: u32,
+    pub enough: bool,
+}
+
+impl Transition<'_> {
+    pub fn day(&self) -> Day {
+        self.from.1.meet_day
+    }
+
+    /// Minutes to spare after getting to the next meeting
+    pub fn slack(&self) -> i32 {
+        self.gap - self.required as i32
+    }
+
+    pub fn tight(&self) -> bool {
+        !self.enough || self.slack() < TIGHT_SLACK
+    }
+}
+
+impl Display for Transition<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "{:?} {} {} {} -> {} {} {}: {} minutes, {} needed",
+            self.day(),
+            self.from.1.time.end,
+            self.from.0.class,
+            self.from.0.section,
+            self.to.1.time.start,
+            self.to.0.class,
+            self.to.0.section,
+            self.gap,
+            self.required
+        ))
+    }
+}
+
+/// Meetings of different sections on `day`, sorted by start time
+fn day_meetings<'a>(schedule: &[&'a Section], day: Day) -> Vec<(&'a Section, &'a DayTime)> {
+    let mut meetings: Vec<_> = schedule
+        .iter()
+        .flat_map(|&x| x.meetings.iter().map(move |y| (x, y)))
+        .filter(|(_, x)| x.meet_day == day)
+        .collect();
+
+    meetings.sort_by_key(|(_, x)| (x.time.start, x.time.end));
+    meetings
+}
+
+/// Every pair of consecutive meetings from different sections, day by day
+pub fn transitions<'a>(schedule: &[&'a Section], passing: &PassingTime) -> Vec<Transition<'a>> {
+    Day::ALL
+        .into_iter()
+        .flat_map(|day| {
+            day_meetings(schedule, day)
+                .windows(2)
+                .filter(|x| !std::ptr::eq(x[0].0, x[1].0))
+                .map(|x| {
+                    let gap = x[0].1.time.gap(&x[1].1.time);
+                    let required = passing.required(x[0].1, x[1].1);
+
+                    Transition {
+                        from: x[0],
+                        to: x[1],
+                        gap,
+                        required,
+                        enough: passing.allows(gap, required),
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Whether every pair of meetings from different sections leaves enough passing time.
+/// Unlike `transitions` this checks all pairs, so meetings inside of longer ones are caught.
+pub fn feasible(schedule: &[&Section], passing: &PassingTime) -> bool {
+    schedule.iter().enumerate().all(|(i, this)| {
+        schedule[i + 1..].iter().all(|other| {
+            this.meetings
+                .iter()
+                .all(|x| other.meetings.iter().all(|y| passing.enough(x, y)))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Campus, Credits, Range, Time, TimeRange};
+
+    fn meeting(start: u32, end: u32, campus: Campus, building: &str) -> DayTime {
+        DayTime {
+            meet_day: Day::Mon,
+            time: TimeRange {
+                start: Time::from_min(start),
+                end: Time::from_min(end),
+            },
+            campus: Some(campus),
+            building: Some(building.to_owned()),
+        }
+    }
+
+    fn section(class: &str, meetings: Vec<DayTime>) -> Section {
+        Section {
+            class: class.to_owned(),
+            section: "001".to_owned(),
+            facility: String::new(),
+            meetings,
+            professor: String::new(),
+            credits: Credits::default(),
+            class_nbr: String::new(),
+            busy: false,
+            dates: String::new(),
+            cart: Vec::new(),
+            cross_listed: Vec::new(),
+            aliases: Vec::new(),
+            course: 0,
+            requisite_met: None,
+            requisites: String::new(),
+        }
+    }
+
+    const PASSING: PassingTime = PassingTime {
+        same_building: 0,
+        same_campus: 10,
+        different_campus: 30,
+        boundary: Boundary::Inclusive,
+    };
+
+    #[test]
+    fn gap() {
+        let a = meeting(600, 660, Campus::North, "A").time;
+        let b = meeting(660, 720, Campus::North, "A").time;
+        let c = meeting(630, 700, Campus::North, "A").time;
+
+        assert_eq!(a.gap(&b), 0);
+        assert_eq!(b.gap(&a), 0);
+        assert_eq!(a.gap(&c), -30);
+        assert!(a.overlap(&b));
+    }
+
+    #[test]
+    fn touching_same_building() {
+        let a = meeting(600, 660, Campus::North, "A");
+        let b = meeting(660, 720, Campus::North, "A");
+
+        assert!(!PASSING.enough(&a, &b));
+        assert!(!PASSING.enough(&b, &a));
+
+        let exclusive = PassingTime {
+            boundary: Boundary::Exclusive,
+            ..PASSING
+        };
+        assert!(exclusive.enough(&a, &b));
+        assert!(exclusive.enough(&b, &a));
+    }
+
+    #[test]
+    fn overlapping_is_never_enough() {
+        let a = meeting(600, 660, Campus::North, "A");
+        let b = meeting(630, 720, Campus::North, "A");
+
+        let exclusive = PassingTime {
+            boundary: Boundary::Exclusive,
+            ..PASSING
+        };
+        assert!(!PASSING.enough(&a, &b));
+        assert!(!exclusive.enough(&a, &b));
+    }
+
+    #[test]
+    fn same_campus() {
+        let a = meeting(600, 660, Campus::North, "A");
+
+        let b = meeting(670, 720, Campus::North, "B");
+        assert!(!PASSING.enough(&a, &b));
+        assert!(PassingTime {
+            boundary: Boundary::Exclusive,
+            ..PASSING
+        }
+        .enough(&a, &b));
+
+        let b = meeting(671, 720, Campus::North, "B");
+        assert!(PASSING.enough(&a, &b));
+    }
+
+    #[test]
+    fn different_campus() {
+        let a = meeting(600, 660, Campus::North, "A");
+
+        let b = meeting(690, 720, Campus::Centinnial, "B");
+        assert!(!PASSING.enough(&a, &b));
+        assert!(!PASSING.enough(&b, &a));
+
+        let b = meeting(691, 720, Campus::Centinnial, "B");
+        assert!(PASSING.enough(&a, &b));
+        assert!(PASSING.enough(&b, &a));
+    }
+
+    #[test]
+    fn different_days() {
+        let a = meeting(600, 660, Campus::North, "A");
+        let b = DayTime {
+            meet_day: Day::Tue,
+            ..a.clone()
+        };
+
+        assert!(PASSING.enough(&a, &b));
+    }
+
+    #[test]
+    fn symmetric() {
+        let a = section("A", vec![meeting(600, 660, Campus::North, "A")]);
+        let b = section("B", vec![meeting(680, 720, Campus::Centinnial, "B")]);
+
+        assert!(!feasible(&[&a, &b], &PASSING));
+        assert!(!feasible(&[&b, &a], &PASSING));
+    }
+
+    #[test]
+    fn midnight() {
+        let a = section("A", vec![meeting(0, 50, Campus::North, "A")]);
+        let b = section("B", vec![meeting(60, 120, Campus::Centinnial, "B")]);
+        let c = section("C", vec![meeting(81, 120, Campus::Centinnial, "B")]);
+
+        assert!(!feasible(&[&a, &b], &PASSING));
+        assert!(feasible(&[&a, &c], &PASSING));
+
+        let transitions = transitions(&[&b, &a], &PASSING);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(transitions[0].from.0.class, "A");
+        assert_eq!(transitions[0].gap, 10);
+        assert!(!transitions[0].enough);
+    }
+
+    #[test]
+    fn contained_meeting() {
+        let a = section(
+            "A",
+            vec![
+                meeting(480, 720, Campus::North, "A"),
+                meeting(540, 560, Campus::North, "A"),
+            ],
+        );
+        let b = section("B", vec![meeting(600, 630, Campus::North, "A")]);
+
+        // The overlap is between meetings that aren't consecutive
+        assert!(transitions(&[&a, &b], &PASSING).iter().all(|x| x.enough));
+        assert!(!feasible(&[&a, &b], &PASSING));
+    }
+
+    #[test]
+    fn tight_transitions() {
+        let a = section("A", vec![meeting(600, 660, Campus::North, "A")]);
+        let b = section("B", vec![meeting(695, 720, Campus::Centinnial, "B")]);
+        let c = section("C", vec![meeting(760, 800, Campus::Centinnial, "B")]);
+
+        let transitions = transitions(&[&c, &b, &a], &PASSING);
+        assert_eq!(transitions.len(), 2);
+        assert!(transitions[0].enough && transitions[0].tight());
+        assert_eq!(transitions[0].slack(), 5);
+        assert!(!transitions[1].tight());
+    }
+}
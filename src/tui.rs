@@ -0,0 +1,175 @@
+use std::fs::File;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint as Size, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::grid::{week_grid, Charset};
+use crate::id::schedule_id;
+use crate::{solve, Metrics, Requirements, Section, Time};
+
+/// IDs of the favorite schedules, which stay the same when they are solved again
+const FAVORITES_FILE: &str = "favorites.json";
+
+struct App<'a, 'b> {
+    classes: &'a Vec<Vec<Section>>,
+    requirements: &'b mut Requirements,
+    schedules: Vec<(Vec<&'a Section>, Metrics)>,
+    state: ListState,
+    favorites: Vec<String>,
+}
+
+impl<'a> App<'a, '_> {
+    fn solve(&mut self) {
+        self.schedules = solve(self.classes, self.requirements);
+        self.state.select((!self.schedules.is_empty()).then_some(0));
+    }
+
+    fn toggle_constraint(&mut self, index: usize) {
+        if let Some((enabled, _)) = self.requirements.constraints.get_mut(index) {
+            *enabled = !*enabled;
+            self.solve();
+        }
+    }
+
+    fn toggle_favorite(&mut self) {
+        let Some(i) = self.state.selected() else {
+            return;
+        };
+
+        let id = schedule_id(&self.schedules[i].0);
+        if let Some(index) = self.favorites.iter().position(|x| *x == id) {
+            self.favorites.remove(index);
+        } else {
+            self.favorites.push(id);
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+                KeyCode::Char('f') => self.toggle_favorite(),
+                KeyCode::Char(c @ '1'..='9') => self.toggle_constraint(c as usize - '1' as usize),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, constraints, help] = Layout::vertical([
+            Size::Fill(1),
+            Size::Length(self.requirements.constraints.len() as u16 + 2),
+            Size::Length(1),
+        ])
+        .areas(frame.area());
+        let [list, grid] = Layout::horizontal([Size::Length(45), Size::Fill(1)]).areas(main);
+
+        let items: Vec<ListItem> = self
+            .schedules
+            .iter()
+            .enumerate()
+            .map(|(i, (schedule, metrics))| {
+                let id = schedule_id(schedule);
+                let favorite = if self.favorites.contains(&id) {
+                    '*'
+                } else {
+                    ' '
+                };
+
+                ListItem::new(format!(
+                    "{favorite}{i:>4} {id} {:>5}cr {}d idle {} tight {}",
+                    metrics.credits.to_string(),
+                    metrics.days,
+                    Time::from_min(metrics.idle),
+                    metrics.tight,
+                ))
+            })
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title(format!("{} Schedules", self.schedules.len())))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+            list,
+            &mut self.state,
+        );
+
+        let lines: Vec<Line> = match self.state.selected() {
+            Some(i) => week_grid(
+                &self.schedules[i].0,
+                grid.width.saturating_sub(2) as usize,
+                Charset::Unicode,
+            )
+            .into_iter()
+            .map(Line::from)
+            .collect(),
+            None => vec![Line::from("No solutions found!")],
+        };
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Week")),
+            grid,
+        );
+
+        let lines: Vec<Line> = self
+            .requirements
+            .constraints
+            .iter()
+            .enumerate()
+            .map(|(i, (enabled, constraint))| {
+                Line::from(format!(
+                    "{} [{}] {constraint}",
+                    i + 1,
+                    if *enabled { 'x' } else { ' ' }
+                ))
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("Constraints")),
+            constraints,
+        );
+
+        frame.render_widget(
+            Line::from("j/k: select  1-9: toggle constraint  f: favorite  q: quit"),
+            help,
+        );
+    }
+}
+
+/// Browse the schedules, toggling constraints and marking favorites
+pub fn run(classes: &Vec<Vec<Section>>, requirements: &mut Requirements) -> std::io::Result<()> {
+    let favorites = File::open(FAVORITES_FILE)
+        .ok()
+        .and_then(|x| serde_json::from_reader(x).ok())
+        .unwrap_or_default();
+
+    let mut app = App {
+        classes,
+        requirements,
+        schedules: Vec::new(),
+        state: ListState::default(),
+        favorites,
+    };
+    app.solve();
+
+    let mut terminal = ratatui::init();
+    let res = app.run(&mut terminal);
+    ratatui::restore();
+
+    serde_json::to_writer_pretty(File::create(FAVORITES_FILE)?, &app.favorites)?;
+
+    res
+}